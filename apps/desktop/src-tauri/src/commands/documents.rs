@@ -0,0 +1,32 @@
+use lore_workspaces::{
+    LoadedDocument, load_document as load_document_impl, save_document as save_document_impl,
+};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+/// `path` is absolute or relative to the open workspace root.
+#[tauri::command]
+pub fn load_document(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+) -> Result<LoadedDocument, AppError> {
+    Ok(load_document_impl(active.root()?, path)?)
+}
+
+/// Pass the `hash` from the last load or save as `expected_hash` to refuse
+/// overwriting changes made outside the app; omit it to force the write.
+#[tauri::command]
+pub fn save_document(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+    content: String,
+    expected_hash: Option<String>,
+) -> Result<LoadedDocument, AppError> {
+    Ok(save_document_impl(
+        active.root()?,
+        path,
+        &content,
+        expected_hash.as_deref(),
+    )?)
+}
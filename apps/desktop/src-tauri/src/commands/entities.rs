@@ -0,0 +1,106 @@
+use std::{fs, path::Path};
+
+use lore_workspaces::{
+    EntitySchema, RenameResult, create_entity as create_entity_impl, create_entity_from_template,
+    entity_schema_for_file as entity_schema_for_file_impl,
+    list_entity_types as list_entity_types_impl, rename_entity as rename_entity_impl,
+};
+use tauri::{AppHandle, Runtime, State};
+use tracing::error;
+
+use crate::core::{
+    config::preferences::get_workspace_preferences, error::AppError,
+    workspace::active::ActiveWorkspace,
+};
+
+#[tauri::command]
+pub fn list_entity_types(
+    active: State<'_, ActiveWorkspace>,
+) -> Result<Vec<EntitySchema>, AppError> {
+    Ok(list_entity_types_impl(active.root()?)?)
+}
+
+/// The schema the properties inspector renders for `path`, or `None` when
+/// the file is not a typed entity.
+#[tauri::command]
+pub fn get_entity_schema(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+) -> Result<Option<EntitySchema>, AppError> {
+    Ok(entity_schema_for_file_impl(active.root()?, path)?)
+}
+
+/// Creates a new entity file and returns its path relative to the workspace.
+/// Characters and locations use the template set in the preferences, or in
+/// the workspace's overrides of them, if any.
+#[tauri::command]
+pub fn create_entity<R: Runtime>(
+    app: AppHandle<R>,
+    active: State<'_, ActiveWorkspace>,
+    entity_type: String,
+    name: String,
+    folder: Option<String>,
+) -> Result<String, AppError> {
+    let root = active.root()?;
+    let folder = folder.as_deref();
+
+    match preferred_template(&app, &root, &entity_type) {
+        Some(template) => Ok(create_entity_from_template(
+            &root,
+            &entity_type,
+            &name,
+            folder,
+            &template,
+        )?),
+        None => Ok(create_entity_impl(&root, &entity_type, &name, folder)?),
+    }
+}
+
+/// Moves an entity and rewrites the links pointing to it. With `dry_run`,
+/// returns the edits without touching any file.
+#[tauri::command]
+pub fn rename_entity(
+    active: State<'_, ActiveWorkspace>,
+    from: String,
+    to: String,
+    dry_run: bool,
+) -> Result<RenameResult, AppError> {
+    Ok(rename_entity_impl(active.root()?, from, to, dry_run)?)
+}
+
+/// The contents of the preferred template file for `entity_type`. A path
+/// that cannot be read falls back to the workspace template.
+fn preferred_template<R: Runtime>(
+    app: &AppHandle<R>,
+    root: &Path,
+    entity_type: &str,
+) -> Option<String> {
+    let preferences = match get_workspace_preferences(app, root) {
+        Ok(preferences) => preferences,
+        Err(e) => {
+            error!("Failed to read preferences: {}", e);
+            return None;
+        }
+    };
+    let template_path = match entity_type {
+        "character" => preferences.default_character_template,
+        "location" => preferences.default_location_template,
+        _ => None,
+    }?;
+    let template_path = template_path.trim();
+    if template_path.is_empty() {
+        return None;
+    }
+
+    // Relative paths are resolved against the workspace root.
+    match fs::read_to_string(root.join(template_path)) {
+        Ok(template) => Some(template),
+        Err(e) => {
+            error!(
+                "Failed to read {} template {}: {}",
+                entity_type, template_path, e
+            );
+            None
+        }
+    }
+}
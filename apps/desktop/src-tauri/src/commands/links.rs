@@ -0,0 +1,23 @@
+use lore_workspaces::{
+    LinkReference, OutgoingLinks, get_backlinks as get_backlinks_impl,
+    get_outgoing_links as get_outgoing_links_impl,
+};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+#[tauri::command]
+pub fn get_backlinks(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+) -> Result<Vec<LinkReference>, AppError> {
+    Ok(get_backlinks_impl(active.root()?, path)?)
+}
+
+#[tauri::command]
+pub fn get_outgoing_links(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+) -> Result<OutgoingLinks, AppError> {
+    Ok(get_outgoing_links_impl(active.root()?, path)?)
+}
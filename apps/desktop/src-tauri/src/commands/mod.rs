@@ -1 +1,9 @@
+pub mod documents;
+pub mod entities;
+pub mod links;
+pub mod query;
+pub mod search;
+pub mod settings;
+pub mod trash;
+pub mod validation;
 pub mod workspace;
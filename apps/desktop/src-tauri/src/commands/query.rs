@@ -0,0 +1,12 @@
+use lore_workspaces::{QueryResult, run_query as run_query_impl};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+#[tauri::command]
+pub fn run_query(
+    active: State<'_, ActiveWorkspace>,
+    query: String,
+) -> Result<QueryResult, AppError> {
+    Ok(run_query_impl(active.root()?, &query)?)
+}
@@ -0,0 +1,16 @@
+use lore_workspaces::{DEFAULT_SEARCH_LIMIT, SearchFilters, SearchHit};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+#[tauri::command]
+pub fn search_workspace(
+    active: State<'_, ActiveWorkspace>,
+    query: String,
+    filters: Option<SearchFilters>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, AppError> {
+    let filters = filters.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    Ok(active.search()?.search(&query, &filters, limit)?)
+}
@@ -0,0 +1,22 @@
+use lore_workspaces::{
+    WorkspaceSettings, load_workspace_settings as load_workspace_settings_impl,
+    save_workspace_settings as save_workspace_settings_impl,
+};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+#[tauri::command]
+pub fn get_workspace_settings(
+    active: State<'_, ActiveWorkspace>,
+) -> Result<WorkspaceSettings, AppError> {
+    Ok(load_workspace_settings_impl(active.root()?)?)
+}
+
+#[tauri::command]
+pub fn save_workspace_settings(
+    active: State<'_, ActiveWorkspace>,
+    settings: WorkspaceSettings,
+) -> Result<(), AppError> {
+    Ok(save_workspace_settings_impl(active.root()?, &settings)?)
+}
@@ -0,0 +1,60 @@
+use chrono::Duration;
+use lore_workspaces::{
+    TrashEntry, delete_from_trash as delete_from_trash_impl, empty_trash as empty_trash_impl,
+    list_trash as list_trash_impl, move_to_trash as move_to_trash_impl,
+    purge_trash as purge_trash_impl, restore_from_trash as restore_from_trash_impl,
+};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+/// Action recorded when the caller does not say why the item was removed.
+const DEFAULT_DELETE_ACTION: &str = "delete";
+
+#[tauri::command]
+pub fn move_to_trash(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+    deleted_by: Option<String>,
+) -> Result<TrashEntry, AppError> {
+    Ok(move_to_trash_impl(
+        active.root()?,
+        path,
+        deleted_by.as_deref().unwrap_or(DEFAULT_DELETE_ACTION),
+    )?)
+}
+
+#[tauri::command]
+pub fn list_trash(active: State<'_, ActiveWorkspace>) -> Result<Vec<TrashEntry>, AppError> {
+    Ok(list_trash_impl(active.root()?)?)
+}
+
+/// Returns the path the item was restored to.
+#[tauri::command]
+pub fn restore_from_trash(
+    active: State<'_, ActiveWorkspace>,
+    id: String,
+) -> Result<String, AppError> {
+    Ok(restore_from_trash_impl(active.root()?, &id)?)
+}
+
+#[tauri::command]
+pub fn delete_from_trash(active: State<'_, ActiveWorkspace>, id: String) -> Result<(), AppError> {
+    Ok(delete_from_trash_impl(active.root()?, &id)?)
+}
+
+#[tauri::command]
+pub fn empty_trash(active: State<'_, ActiveWorkspace>) -> Result<usize, AppError> {
+    Ok(empty_trash_impl(active.root()?)?)
+}
+
+#[tauri::command]
+pub fn purge_trash(
+    active: State<'_, ActiveWorkspace>,
+    max_age_days: u32,
+) -> Result<usize, AppError> {
+    Ok(purge_trash_impl(
+        active.root()?,
+        Duration::days(i64::from(max_age_days)),
+    )?)
+}
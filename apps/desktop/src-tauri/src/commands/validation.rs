@@ -0,0 +1,21 @@
+use lore_workspaces::{
+    Diagnostic, validate_document as validate_document_impl,
+    validate_workspace as validate_workspace_impl,
+};
+use tauri::State;
+
+use crate::core::{error::AppError, workspace::active::ActiveWorkspace};
+
+#[tauri::command]
+pub fn validate_document(
+    active: State<'_, ActiveWorkspace>,
+    path: String,
+) -> Result<Vec<Diagnostic>, AppError> {
+    Ok(validate_document_impl(active.root()?, path)?)
+}
+
+/// Every problem in the open workspace, for the problems panel.
+#[tauri::command]
+pub fn validate_workspace(active: State<'_, ActiveWorkspace>) -> Result<Vec<Diagnostic>, AppError> {
+    Ok(validate_workspace_impl(active.root()?)?)
+}
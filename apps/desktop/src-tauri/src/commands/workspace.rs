@@ -1,14 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Duration;
 use lore_workspaces::{
-    CreateWorkspaceRequest, CreateWorkspaceResult, WorkspaceTemplateSummary,
-    create_workspace as create_workspace_impl, list_workspace_templates as list_templates_impl,
+    CreateWorkspaceRequest, CreateWorkspaceResult, DEFAULT_TRASH_RETENTION_DAYS, OpenedWorkspace,
+    WorkspaceTemplateMeta, WorkspaceTemplateSummary, WorkspaceTree,
+    create_workspace_from as create_workspace_impl,
+    list_workspace_templates_from as list_templates_impl, open_workspace as open_workspace_impl,
+    purge_trash, save_workspace_as_template as save_as_template_impl, scan_workspace,
+};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tracing::error;
+
+use crate::core::{
+    error::AppError, recent::store::update_recent_workspaces, workspace::active::ActiveWorkspace,
 };
 
+/// Folder under the app data directory holding user-defined workspace templates.
+const USER_TEMPLATES_DIR: &str = "templates";
+
+#[tauri::command]
+pub fn list_workspace_templates<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<WorkspaceTemplateSummary>, AppError> {
+    Ok(list_templates_impl(&user_templates_dir(&app)?))
+}
+
+#[tauri::command]
+pub fn create_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    request: CreateWorkspaceRequest,
+) -> Result<CreateWorkspaceResult, AppError> {
+    let result = create_workspace_impl(request, &user_templates_dir(&app)?)?;
+    record_recent_workspace(&app, &result.id, &result.root_path, &result.name);
+    app.state::<ActiveWorkspace>()
+        .activate(&app, Path::new(&result.root_path));
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn open_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<OpenedWorkspace, AppError> {
+    let opened = open_workspace_impl(path.trim())?;
+    record_recent_workspace(&app, &opened.id, &opened.root_path, &opened.name);
+    purge_expired_trash(&opened.root_path);
+    app.state::<ActiveWorkspace>()
+        .activate(&app, Path::new(&opened.root_path));
+    Ok(opened)
+}
+
+/// Stops watching the open workspace.
+#[tauri::command]
+pub fn close_workspace(active: State<'_, ActiveWorkspace>) {
+    active.close();
+}
+
 #[tauri::command]
-pub fn list_workspace_templates() -> Result<Vec<WorkspaceTemplateSummary>, String> {
-    Ok(list_templates_impl())
+pub fn save_workspace_as_template<R: Runtime>(
+    app: AppHandle<R>,
+    root: String,
+    template: WorkspaceTemplateMeta,
+    include_content: bool,
+) -> Result<WorkspaceTemplateSummary, AppError> {
+    Ok(save_as_template_impl(
+        root.trim(),
+        template,
+        include_content,
+        &user_templates_dir(&app)?,
+    )?)
 }
 
 #[tauri::command]
-pub fn create_workspace(request: CreateWorkspaceRequest) -> Result<CreateWorkspaceResult, String> {
-    create_workspace_impl(request).map_err(|error| error.to_string())
+pub fn list_workspace_tree(root: String) -> Result<WorkspaceTree, AppError> {
+    Ok(scan_workspace(root.trim())?)
+}
+
+fn user_templates_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+    Ok(app.path().app_data_dir()?.join(USER_TEMPLATES_DIR))
+}
+
+/// Trash retention is housekeeping; a failure must not block opening.
+fn purge_expired_trash(root: &str) {
+    let max_age = Duration::days(i64::from(DEFAULT_TRASH_RETENTION_DAYS));
+    if let Err(e) = purge_trash(root, max_age) {
+        error!("Failed to purge expired trash in {}: {}", root, e);
+    }
+}
+
+/// A failure to update the recent list must not fail the create/open itself.
+fn record_recent_workspace<R: Runtime>(app: &AppHandle<R>, id: &str, path: &str, name: &str) {
+    if let Err(e) = update_recent_workspaces(app, |recent| recent.record(id, path, name)) {
+        error!("Failed to record recent workspace {}: {}", path, e);
+    }
 }
@@ -1,8 +1,10 @@
 use crate::core::config::preferences::{
-    get_current_preferences, AppPreferences, PREFERENCES_FILE, PREFERENCES_KEY,
+    AppPreferences, PREFERENCES_FILE, PREFERENCES_KEY, get_current_preferences,
+    get_workspace_preferences,
 };
+use crate::core::workspace::active::ActiveWorkspace;
 use serde_json::json;
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Runtime, State};
 use tauri_plugin_store::StoreExt;
 
 #[tauri::command]
@@ -10,6 +12,19 @@ pub fn get_preferences<R: Runtime>(app: AppHandle<R>) -> Result<AppPreferences,
     get_current_preferences(&app)
 }
 
+/// The preferences in effect: the app-wide ones with the open workspace's
+/// overrides applied, or the app-wide ones alone when no workspace is open.
+#[tauri::command]
+pub fn get_effective_preferences<R: Runtime>(
+    app: AppHandle<R>,
+    active: State<'_, ActiveWorkspace>,
+) -> Result<AppPreferences, String> {
+    match active.root() {
+        Ok(root) => get_workspace_preferences(&app, &root),
+        Err(_) => get_current_preferences(&app),
+    }
+}
+
 #[tauri::command]
 pub fn save_preferences<R: Runtime>(
     app: AppHandle<R>,
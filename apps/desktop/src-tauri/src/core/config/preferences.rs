@@ -1,7 +1,11 @@
+use std::path::Path;
+
+use lore_workspaces::load_workspace_settings;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{Map, Value, json};
 use tauri::{AppHandle, Runtime};
 use tauri_plugin_store::StoreExt;
+use tracing::warn;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AppPreferences {
@@ -47,6 +51,17 @@ impl Default for AppPreferences {
 pub const PREFERENCES_KEY: &str = "preferences";
 pub const PREFERENCES_FILE: &str = "preferences.dat";
 
+/// Preferences a workspace may override in the `[preferences]` table of its
+/// `.lore/settings.toml`. Template paths are read from disk, so a workspace
+/// must not choose them; it has `.lore/templates/` instead.
+pub const WORKSPACE_PREFERENCE_KEYS: &[&str] = &[
+    "theme",
+    "language",
+    "font_size",
+    "auto_save",
+    "auto_save_interval_seconds",
+];
+
 pub fn init_preferences<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     let store = app.store(PREFERENCES_FILE).map_err(|e| e.to_string())?;
 
@@ -72,3 +87,63 @@ pub fn get_current_preferences<R: Runtime>(app: &AppHandle<R>) -> Result<AppPref
         None => Ok(AppPreferences::default()),
     }
 }
+
+/// The app preferences with the overrides of the workspace at `root` applied.
+pub fn get_workspace_preferences<R: Runtime>(
+    app: &AppHandle<R>,
+    root: &Path,
+) -> Result<AppPreferences, String> {
+    let preferences = get_current_preferences(app)?;
+    let settings = load_workspace_settings(root).map_err(|e| e.to_string())?;
+    Ok(apply_workspace_overrides(
+        preferences,
+        &settings.preferences,
+    ))
+}
+
+/// Applies each override in turn. Keys a workspace may not override, and
+/// values of the wrong type, are skipped.
+pub fn apply_workspace_overrides(
+    preferences: AppPreferences,
+    overrides: &Map<String, Value>,
+) -> AppPreferences {
+    let mut merged = preferences;
+    for (key, value) in overrides {
+        if !WORKSPACE_PREFERENCE_KEYS.contains(&key.as_str()) {
+            warn!("Ignoring workspace override of preference '{}'", key);
+            continue;
+        }
+
+        let mut candidate = json!(merged);
+        candidate[key] = value.clone();
+        match serde_json::from_value(candidate) {
+            Ok(preferences) => merged = preferences,
+            Err(e) => warn!("Ignoring workspace override of preference '{}': {}", key, e),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, json};
+
+    use super::{AppPreferences, apply_workspace_overrides};
+
+    #[test]
+    fn workspaces_cannot_override_template_paths() {
+        let mut overrides = Map::new();
+        overrides.insert("font_size".into(), json!(18));
+        overrides.insert("theme".into(), json!(42));
+        overrides.insert(
+            "default_character_template".into(),
+            json!("/home/me/.ssh/id_ed25519"),
+        );
+
+        let merged = apply_workspace_overrides(AppPreferences::default(), &overrides);
+
+        assert_eq!(merged.font_size, 18);
+        assert_eq!(merged.theme, "system");
+        assert_eq!(merged.default_character_template, None);
+    }
+}
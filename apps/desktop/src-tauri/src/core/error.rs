@@ -10,6 +10,15 @@ pub enum AppError {
 
     #[error("Filesystem error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Tauri error: {0}")]
+    Tauri(#[from] tauri::Error),
+
+    #[error("No workspace is open.")]
+    NoWorkspaceOpen,
+
+    #[error("{0}")]
+    Workspace(#[from] lore_workspaces::WorkspaceError),
 }
 
 impl serde::Serialize for AppError {
@@ -1,2 +1,4 @@
 pub mod config;
 pub mod error;
+pub mod recent;
+pub mod workspace;
@@ -0,0 +1,45 @@
+use lore_workspaces::RecentWorkspace;
+use tauri::{AppHandle, Runtime};
+
+use crate::core::{
+    error::AppError,
+    recent::store::{load_recent_workspaces, update_recent_workspaces},
+};
+
+#[tauri::command]
+pub fn list_recent_workspaces<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<RecentWorkspace>, AppError> {
+    Ok(load_recent_workspaces(&app)?.list())
+}
+
+#[tauri::command]
+pub fn pin_recent_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    pinned: bool,
+) -> Result<Vec<RecentWorkspace>, AppError> {
+    let recent = update_recent_workspaces(&app, |recent| {
+        recent.set_pinned(&path, pinned);
+    })?;
+    Ok(recent.list())
+}
+
+#[tauri::command]
+pub fn remove_recent_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<Vec<RecentWorkspace>, AppError> {
+    let recent = update_recent_workspaces(&app, |recent| {
+        recent.remove(&path);
+    })?;
+    Ok(recent.list())
+}
+
+#[tauri::command]
+pub fn clear_recent_workspaces<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<RecentWorkspace>, AppError> {
+    let recent = update_recent_workspaces(&app, |recent| recent.clear())?;
+    Ok(recent.list())
+}
@@ -0,0 +1,35 @@
+use lore_workspaces::RecentWorkspaces;
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::core::error::AppError;
+
+pub const RECENT_WORKSPACES_KEY: &str = "recent_workspaces";
+pub const RECENT_WORKSPACES_FILE: &str = "recent-workspaces.dat";
+
+pub fn load_recent_workspaces<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<RecentWorkspaces, AppError> {
+    let store = app.store(RECENT_WORKSPACES_FILE)?;
+
+    match store.get(RECENT_WORKSPACES_KEY) {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(RecentWorkspaces::default()),
+    }
+}
+
+/// Loads the recent list, applies `update` and persists the result.
+pub fn update_recent_workspaces<R: Runtime>(
+    app: &AppHandle<R>,
+    update: impl FnOnce(&mut RecentWorkspaces),
+) -> Result<RecentWorkspaces, AppError> {
+    let mut recent = load_recent_workspaces(app)?;
+    update(&mut recent);
+
+    let store = app.store(RECENT_WORKSPACES_FILE)?;
+    store.set(RECENT_WORKSPACES_KEY, json!(recent));
+    store.save()?;
+
+    Ok(recent)
+}
@@ -0,0 +1,158 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use lore_workspaces::{SearchIndex, WorkspaceWatcher, refresh_metadata_cache};
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{error, info};
+
+use crate::core::error::AppError;
+
+/// Emitted with a [`lore_workspaces::WorkspaceChanged`] payload for every
+/// debounced batch of filesystem changes in the open workspace.
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace://changed";
+
+/// The workspace currently open in the window, managed as Tauri state.
+#[derive(Default)]
+pub struct ActiveWorkspace {
+    inner: Mutex<Option<OpenWorkspace>>,
+}
+
+struct OpenWorkspace {
+    root: PathBuf,
+    /// Dropping the watcher stops it.
+    watcher: Option<WorkspaceWatcher>,
+    /// Kept current by the watcher; `None` if it could not be opened.
+    search: Option<Arc<SearchIndex>>,
+}
+
+impl ActiveWorkspace {
+    /// Makes `root` the open workspace and starts watching it, replacing any
+    /// previous one. A watcher that fails to start is logged, not fatal: the
+    /// workspace stays usable without live updates.
+    pub fn activate<R: Runtime>(&self, app: &AppHandle<R>, root: &Path) {
+        let already_watching = self
+            .lock()
+            .as_ref()
+            .is_some_and(|open| open.root == root && open.watcher.is_some());
+        if already_watching {
+            return;
+        }
+
+        refresh_metadata(root);
+        let search = open_search_index(root);
+
+        let handle = app.clone();
+        let watched_search = search.clone();
+        let watcher = WorkspaceWatcher::start(root, move |changed| {
+            if let Some(search) = &watched_search
+                && let Err(e) = search.apply(&changed)
+            {
+                error!("Failed to update the search index: {}", e);
+            }
+            if let Err(e) = handle.emit(WORKSPACE_CHANGED_EVENT, changed) {
+                error!("Failed to emit workspace change event: {}", e);
+            }
+        });
+
+        let watcher = match watcher {
+            Ok(watcher) => {
+                info!("Watching workspace {}", root.display());
+                Some(watcher)
+            }
+            Err(e) => {
+                error!("Failed to watch workspace {}: {}", root.display(), e);
+                None
+            }
+        };
+
+        *self.lock() = Some(OpenWorkspace {
+            root: root.to_path_buf(),
+            watcher,
+            search,
+        });
+    }
+
+    pub fn close(&self) {
+        *self.lock() = None;
+    }
+
+    /// Root of the open workspace; commands that touch workspace files are
+    /// confined to it.
+    pub fn root(&self) -> Result<PathBuf, AppError> {
+        self.lock()
+            .as_ref()
+            .map(|open| open.root.clone())
+            .ok_or(AppError::NoWorkspaceOpen)
+    }
+
+    /// Search index of the open workspace, opened and rebuilt on the spot if
+    /// that failed when the workspace was activated.
+    pub fn search(&self) -> Result<Arc<SearchIndex>, AppError> {
+        let mut guard = self.lock();
+        let open = guard.as_mut().ok_or(AppError::NoWorkspaceOpen)?;
+        if let Some(search) = &open.search {
+            return Ok(search.clone());
+        }
+
+        let search = Arc::new(SearchIndex::open(&open.root)?);
+        search.rebuild()?;
+        open.search = Some(search.clone());
+        Ok(search)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<OpenWorkspace>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Re-parses in the background the files that changed since the workspace
+/// was last open, so the first query or backlink lookup does not have to.
+fn refresh_metadata(root: &Path) {
+    let root = root.to_path_buf();
+    thread::spawn(move || match refresh_metadata_cache(&root) {
+        Ok(refresh) => info!(
+            "Metadata cache of {} is current: {} files, {} parsed, {} removed",
+            root.display(),
+            refresh.files,
+            refresh.parsed,
+            refresh.removed
+        ),
+        Err(e) => error!("Failed to refresh the metadata cache: {}", e),
+    });
+}
+
+/// Opens the search index of `root` and brings it up to date in the
+/// background: rebuilt when it was missing or outdated, otherwise reconciled
+/// with edits made while the workspace was closed. Searches return partial
+/// results until then.
+fn open_search_index(root: &Path) -> Option<Arc<SearchIndex>> {
+    let search = match SearchIndex::open(root) {
+        Ok(search) => Arc::new(search),
+        Err(e) => {
+            error!(
+                "Failed to open the search index of {}: {}",
+                root.display(),
+                e
+            );
+            return None;
+        }
+    };
+
+    let updating = search.clone();
+    if search.needs_rebuild() {
+        thread::spawn(move || match updating.rebuild() {
+            Ok(count) => info!("Indexed {} documents for search", count),
+            Err(e) => error!("Failed to rebuild the search index: {}", e),
+        });
+    } else {
+        thread::spawn(move || match updating.reconcile() {
+            Ok(count) => info!("Re-indexed {} changed documents for search", count),
+            Err(e) => error!("Failed to update the search index: {}", e),
+        });
+    }
+
+    Some(search)
+}
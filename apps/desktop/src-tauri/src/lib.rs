@@ -2,6 +2,8 @@ mod commands;
 mod core;
 mod system_info;
 use core::config::{commands as config_commands, preferences};
+use core::recent::commands as recent_commands;
+use core::workspace::active::ActiveWorkspace;
 use tauri_plugin_tracing::{Builder, Rotation, RotationStrategy};
 use tracing::{error, info};
 
@@ -42,14 +44,44 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .manage(ActiveWorkspace::default())
         .invoke_handler(tauri::generate_handler![
             config_commands::get_preferences,
+            config_commands::get_effective_preferences,
             config_commands::save_preferences,
             config_commands::set_theme,
             config_commands::set_language,
             config_commands::update_last_project,
             commands::workspace::list_workspace_templates,
             commands::workspace::create_workspace,
+            commands::workspace::open_workspace,
+            commands::workspace::close_workspace,
+            commands::workspace::save_workspace_as_template,
+            commands::workspace::list_workspace_tree,
+            commands::documents::load_document,
+            commands::documents::save_document,
+            commands::entities::list_entity_types,
+            commands::entities::get_entity_schema,
+            commands::entities::create_entity,
+            commands::entities::rename_entity,
+            commands::links::get_backlinks,
+            commands::links::get_outgoing_links,
+            commands::query::run_query,
+            commands::search::search_workspace,
+            commands::settings::get_workspace_settings,
+            commands::settings::save_workspace_settings,
+            commands::trash::move_to_trash,
+            commands::trash::list_trash,
+            commands::trash::restore_from_trash,
+            commands::trash::delete_from_trash,
+            commands::trash::empty_trash,
+            commands::trash::purge_trash,
+            commands::validation::validate_document,
+            commands::validation::validate_workspace,
+            recent_commands::list_recent_workspaces,
+            recent_commands::pin_recent_workspace,
+            recent_commands::remove_recent_workspace,
+            recent_commands::clear_recent_workspaces,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,11 +1,16 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { LoadedDocument } from '@/types/document';
 
-// Document Tauri command stubs — real implementations land with lore-editor crate
-
-export async function loadDocument(path: string): Promise<unknown> {
+export async function loadDocument(path: string): Promise<LoadedDocument> {
   return invoke('load_document', { path });
 }
 
-export async function saveDocument(path: string, content: string): Promise<void> {
-  return invoke('save_document', { path, content });
+// Pass the `hash` of the last loaded or saved version to get a conflict error
+// instead of overwriting edits made outside the app.
+export async function saveDocument(
+  path: string,
+  content: string,
+  expectedHash?: string,
+): Promise<LoadedDocument> {
+  return invoke('save_document', { content, expectedHash, path });
 }
@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { EntitySchema, RenameResult } from '@/types/entity';
+
+export async function listEntityTypes(): Promise<EntitySchema[]> {
+  return invoke('list_entity_types');
+}
+
+// Resolves to null when the file is not a typed entity.
+export async function getEntitySchema(path: string): Promise<EntitySchema | null> {
+  return invoke('get_entity_schema', { path });
+}
+
+// Resolves to the new file's path, relative to the workspace root. Without a
+// folder, the file goes in the type's default folder.
+export async function createEntity(
+  entityType: string,
+  name: string,
+  folder?: string,
+): Promise<string> {
+  return invoke('create_entity', { entityType, folder, name });
+}
+
+// Call with `dryRun` first to preview the link edits, then again to apply.
+export async function renameEntity(
+  from: string,
+  to: string,
+  dryRun: boolean,
+): Promise<RenameResult> {
+  return invoke('rename_entity', { dryRun, from, to });
+}
@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { LinkReference, OutgoingLinks } from '@/types/links';
+
+// Links from other documents that resolve to `path`.
+export async function getBacklinks(path: string): Promise<LinkReference[]> {
+  return invoke('get_backlinks', { path });
+}
+
+export async function getOutgoingLinks(path: string): Promise<OutgoingLinks> {
+  return invoke('get_outgoing_links', { path });
+}
@@ -0,0 +1,8 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { QueryResult } from '@/types/query';
+
+// Runs a query such as `type = character AND faction -> [[Iron Court]] SORT name`
+// over the frontmatter of every document in the open workspace.
+export async function runQuery(query: string): Promise<QueryResult> {
+  return invoke('run_query', { query });
+}
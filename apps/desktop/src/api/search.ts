@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { SearchFilters, SearchHit } from '@/types/search';
+
+// Ranked full-text matches across titles, frontmatter and bodies.
+export async function searchWorkspace(
+  query: string,
+  filters?: SearchFilters,
+  limit?: number,
+): Promise<SearchHit[]> {
+  return invoke('search_workspace', { filters, limit, query });
+}
@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { WorkspaceSettings } from '@/types/settings';
+
+export async function getWorkspaceSettings(): Promise<WorkspaceSettings> {
+  return invoke('get_workspace_settings');
+}
+
+// Writes `.lore/settings.toml`; keys the app does not know are kept.
+export async function saveWorkspaceSettings(settings: WorkspaceSettings): Promise<void> {
+  return invoke('save_workspace_settings', { settings });
+}
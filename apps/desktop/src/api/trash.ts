@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TrashEntry } from '@/types/document';
+
+export async function moveToTrash(path: string, deletedBy?: string): Promise<TrashEntry> {
+  return invoke('move_to_trash', { deletedBy, path });
+}
+
+export async function listTrash(): Promise<TrashEntry[]> {
+  return invoke('list_trash');
+}
+
+// Resolves to the path the item was restored to, which differs from the
+// original when something else now occupies it.
+export async function restoreFromTrash(id: string): Promise<string> {
+  return invoke('restore_from_trash', { id });
+}
+
+export async function deleteFromTrash(id: string): Promise<void> {
+  return invoke('delete_from_trash', { id });
+}
+
+export async function emptyTrash(): Promise<number> {
+  return invoke('empty_trash');
+}
+
+export async function purgeTrash(maxAgeDays: number): Promise<number> {
+  return invoke('purge_trash', { maxAgeDays });
+}
@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Diagnostic } from '@/types/validation';
+
+export async function validateDocument(path: string): Promise<Diagnostic[]> {
+  return invoke('validate_document', { path });
+}
+
+// Sorted by path, then line, for the problems panel.
+export async function validateWorkspace(): Promise<Diagnostic[]> {
+  return invoke('validate_workspace');
+}
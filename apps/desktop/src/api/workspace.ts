@@ -1,16 +1,32 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
   CreateWorkspaceRequest,
   CreateWorkspaceResult,
+  OpenedWorkspace,
+  RecentWorkspace,
+  WorkspaceTemplateMeta,
+  WorkspaceChanged,
   WorkspaceTemplateSummary,
+  WorkspaceTree,
 } from '@features/workspace-wizard/types';
 
 // Workspace Tauri command stubs — real implementations land with lore-workspaces crate
 
-export async function openWorkspace(path: string): Promise<void> {
+export async function openWorkspace(path: string): Promise<OpenedWorkspace> {
   return invoke('open_workspace', { path });
 }
 
+export async function closeWorkspace(): Promise<void> {
+  return invoke('close_workspace');
+}
+
+export async function onWorkspaceChanged(
+  handler: (changed: WorkspaceChanged) => void,
+): Promise<UnlistenFn> {
+  return listen<WorkspaceChanged>('workspace://changed', (event) => handler(event.payload));
+}
+
 export async function listWorkspaceTemplates(): Promise<WorkspaceTemplateSummary[]> {
   return invoke('list_workspace_templates');
 }
@@ -21,6 +37,33 @@ export async function createWorkspace(
   return invoke('create_workspace', { request });
 }
 
-export async function listRecentWorkspaces(): Promise<unknown[]> {
+export async function saveWorkspaceAsTemplate(
+  root: string,
+  template: WorkspaceTemplateMeta,
+  includeContent: boolean,
+): Promise<WorkspaceTemplateSummary> {
+  return invoke('save_workspace_as_template', { root, template, includeContent });
+}
+
+export async function listWorkspaceTree(root: string): Promise<WorkspaceTree> {
+  return invoke('list_workspace_tree', { root });
+}
+
+export async function listRecentWorkspaces(): Promise<RecentWorkspace[]> {
   return invoke('list_recent_workspaces');
 }
+
+export async function pinRecentWorkspace(
+  path: string,
+  pinned: boolean,
+): Promise<RecentWorkspace[]> {
+  return invoke('pin_recent_workspace', { path, pinned });
+}
+
+export async function removeRecentWorkspace(path: string): Promise<RecentWorkspace[]> {
+  return invoke('remove_recent_workspace', { path });
+}
+
+export async function clearRecentWorkspaces(): Promise<RecentWorkspace[]> {
+  return invoke('clear_recent_workspaces');
+}
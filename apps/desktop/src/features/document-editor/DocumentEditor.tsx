@@ -1,6 +1,7 @@
 import { Fragment, type CSSProperties } from 'react';
 import { cn } from '@lore/ui/lib/utils';
 import { getActiveEntry, useEditorShellStore } from '@/store/editor-shell';
+import { LoreQueryTable, parseQueryBlock } from './LoreQueryTable';
 
 const MENTION_PATTERN = /\[\[([^\]]+)\]\]/g;
 
@@ -103,6 +104,8 @@ export function DocumentEditor() {
         ) : (
           entry.paragraphs.map((paragraph, index) => {
             const paragraphId = `${entry.id}-p${index}`;
+            const query = parseQueryBlock(paragraph);
+            if (query !== null) return <LoreQueryTable key={paragraphId} query={query} />;
             const focused = !codex && state.focusedParagraphId === paragraphId;
             const dimmed =
               !codex && state.focusModeEnabled && state.focusedParagraphId !== null && !focused;
@@ -0,0 +1,82 @@
+import { useEffect, useState } from 'react';
+import { runQuery } from '@/api/query';
+import type { QueryResult, QueryValue } from '@/types/query';
+
+const QUERY_BLOCK_PATTERN = /^```lore-query[ \t]*\n([\s\S]*?)\n?```\s*$/;
+
+// The query inside a fenced ```lore-query block, or null for any other text.
+export function parseQueryBlock(text: string) {
+  const match = QUERY_BLOCK_PATTERN.exec(text.trim());
+  return match ? match[1].trim() : null;
+}
+
+function formatValue(value: QueryValue): string {
+  if (value === null) return '';
+  if (Array.isArray(value)) return value.map(formatValue).join(', ');
+  if (typeof value === 'object') return JSON.stringify(value);
+  return String(value).replace(/^\[\[([^\]|#]+)(?:[^\]]*)\]\]$/, '$1');
+}
+
+export function LoreQueryTable({ query }: { query: string }) {
+  const [result, setResult] = useState<QueryResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setError(null);
+    void runQuery(query)
+      .then((nextResult) => {
+        if (!cancelled) setResult(nextResult);
+      })
+      .catch((nextError) => {
+        if (cancelled) return;
+        setResult(null);
+        setError(typeof nextError === 'string' ? nextError : 'Failed to run the query.');
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [query]);
+
+  if (error) {
+    return (
+      <div className="mb-6 rounded-md border border-destructive/40 px-3.5 py-2.5 text-sm text-destructive">
+        {error}
+      </div>
+    );
+  }
+  if (!result) return null;
+
+  return (
+    <div className="mb-6 overflow-x-auto">
+      <table className="w-full border-collapse text-sm text-body">
+        <thead>
+          <tr>
+            {result.columns.map((column) => (
+              <th
+                className="border-b border-border px-2.5 py-2 text-left text-[10px] font-semibold tracking-[0.12em] text-faint uppercase"
+                key={column}
+              >
+                {column}
+              </th>
+            ))}
+          </tr>
+        </thead>
+        <tbody>
+          {result.rows.map((row) => (
+            <tr key={row.path}>
+              {row.values.map((value, index) => (
+                <td className="border-b border-border px-2.5 py-2" key={result.columns[index]}>
+                  {formatValue(value)}
+                </td>
+              ))}
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      {result.rows.length === 0 ? (
+        <p className="py-2.5 text-sm text-faint italic">No entries match this query.</p>
+      ) : null}
+    </div>
+  );
+}
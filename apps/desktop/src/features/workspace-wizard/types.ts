@@ -29,8 +29,16 @@ export interface WorkspaceTemplateSummary {
   supportsCreation: boolean;
 }
 
+export interface WorkspaceTemplateMeta {
+  description?: string;
+  displayName: string;
+  id?: string;
+  sortOrder?: number;
+}
+
 export interface CreateWorkspaceRequest {
   appVersion: string;
+  author?: string;
   name: string;
   parentPath: string;
   templateId: string;
@@ -42,9 +50,67 @@ export interface WorkspaceVersion {
 
 export interface CreateWorkspaceResult {
   createdAt: string;
+  id: string;
+  manifestPath: string;
+  name: string;
+  rootPath: string;
+  templateId: string;
+  workspaceVersion: WorkspaceVersion;
+}
+
+export interface OpenedWorkspace {
+  createdAt: string;
+  createdWith: string;
+  id: string;
   manifestPath: string;
+  migratedFrom: WorkspaceVersion | null;
   name: string;
   rootPath: string;
   templateId: string;
   workspaceVersion: WorkspaceVersion;
 }
+
+export interface RecentWorkspace {
+  exists: boolean;
+  id: string | null;
+  lastOpened: string;
+  name: string;
+  path: string;
+  pinned: boolean;
+}
+
+export type WorkspaceEntryKind =
+  | 'folder'
+  | 'character'
+  | 'location'
+  | 'entity'
+  | 'document'
+  | 'image'
+  | 'other';
+
+export interface WorkspaceEntry {
+  children?: WorkspaceEntry[];
+  entityType: string | null;
+  kind: WorkspaceEntryKind;
+  modifiedAt: string | null;
+  name: string;
+  path: string;
+  size: number;
+}
+
+export interface WorkspaceTree {
+  entries: WorkspaceEntry[];
+  rootPath: string;
+}
+
+export type WorkspaceChange =
+  | { entry: WorkspaceEntry; kind: 'created' }
+  | { entry: WorkspaceEntry; kind: 'modified' }
+  | { kind: 'removed'; path: string }
+  | { entry: WorkspaceEntry; from: string; kind: 'renamed'; to: string };
+
+export interface WorkspaceChanged {
+  changes: WorkspaceChange[];
+  rescan: boolean;
+  rootPath: string;
+}
@@ -0,0 +1,17 @@
+export interface LoadedDocument {
+  body: string;
+  content: string;
+  frontmatter: Record<string, unknown>;
+  frontmatterError: string | null;
+  hash: string;
+  modifiedAt: string | null;
+  path: string;
+}
+
+export interface TrashEntry {
+  deletedAt: string;
+  deletedBy: string;
+  id: string;
+  isDir: boolean;
+  originalPath: string;
+}
@@ -0,0 +1,40 @@
+export type FieldKind = 'date' | 'enum' | 'link' | 'list' | 'number' | 'string';
+
+export interface FieldSchema {
+  default: unknown;
+  items: FieldKind | null;
+  kind: FieldKind;
+  label: string | null;
+  max: number | null;
+  min: number | null;
+  name: string;
+  options: string[];
+  required: boolean;
+  target: string | null;
+}
+
+export interface EntitySchema {
+  description: string | null;
+  displayName: string;
+  extension: string;
+  fields: FieldSchema[];
+  folder: string | null;
+  id: string;
+  isBuiltin: boolean;
+}
+
+export interface LinkEdit {
+  after: string;
+  before: string;
+  column: number;
+  field: string | null;
+  line: number;
+  path: string;
+}
+
+export interface RenameResult {
+  applied: boolean;
+  edits: LinkEdit[];
+  from: string;
+  to: string;
+}
@@ -0,0 +1,15 @@
+export interface LinkReference {
+  alias: string | null;
+  column: number;
+  field: string | null;
+  heading: string | null;
+  line: number;
+  resolvedPath: string | null;
+  source: string;
+  target: string;
+}
+
+export interface OutgoingLinks {
+  resolved: LinkReference[];
+  unresolved: LinkReference[];
+}
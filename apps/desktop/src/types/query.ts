@@ -0,0 +1,19 @@
+export type QueryValue =
+  | boolean
+  | number
+  | string
+  | null
+  | QueryValue[]
+  | { [key: string]: QueryValue };
+
+export interface QueryRow {
+  entityType: string | null;
+  path: string;
+  // One per column; null where the field is not set.
+  values: QueryValue[];
+}
+
+export interface QueryResult {
+  columns: string[];
+  rows: QueryRow[];
+}
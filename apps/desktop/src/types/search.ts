@@ -0,0 +1,14 @@
+export interface SearchFilters {
+  entityTypes?: string[];
+  folder?: string | null;
+}
+
+export interface SearchHit {
+  entityType: string | null;
+  // Ranges of `snippet` that matched the query, in UTF-16 code units.
+  highlights: [number, number][];
+  path: string;
+  score: number;
+  snippet: string;
+  title: string;
+}
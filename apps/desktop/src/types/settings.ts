@@ -0,0 +1,23 @@
+// Mirrors `.lore/settings.toml`, so keys keep its snake_case.
+export type LinkStyle = 'wiki' | 'markdown';
+export type ExportFormat = 'markdown' | 'html' | 'pdf';
+
+export interface ExportSettings {
+  format: ExportFormat;
+  include_frontmatter: boolean;
+  output_folder?: string;
+}
+
+export interface WorkspaceSettings {
+  // strftime pattern, e.g. `%d %B %Y`.
+  date_format: string;
+  // Folder new entities go to, by entity type id.
+  entity_folders?: Record<string, string>;
+  export: ExportSettings;
+  link_style: LinkStyle;
+  // App preferences overridden in this workspace; `null` restores the app-wide value.
+  preferences?: Record<string, unknown>;
+  // Follows the app language when unset.
+  spellcheck_language?: string;
+  [key: string]: unknown;
+}
@@ -0,0 +1,22 @@
+export type DiagnosticSeverity = 'error' | 'warning';
+
+export type DiagnosticCode =
+  | 'brokenLink'
+  | 'invalidFrontmatter'
+  | 'malformedType'
+  | 'missingField'
+  | 'outOfRange'
+  | 'unknownOption'
+  | 'unknownType'
+  | 'wrongLinkTarget'
+  | 'wrongType';
+
+export interface Diagnostic {
+  code: DiagnosticCode;
+  column: number | null;
+  field: string | null;
+  line: number | null;
+  message: string;
+  path: string;
+  severity: DiagnosticSeverity;
+}
@@ -0,0 +1,511 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use thiserror::Error;
+
+const FENCE: &str = "---";
+const CLOSING_FENCES: &[&str] = &["---", "..."];
+const BOM: char = '\u{feff}';
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum DocumentError {
+    #[error("The frontmatter opened on line {line} is never closed with `---`.")]
+    UnclosedFrontmatter { line: usize },
+
+    #[error("Invalid frontmatter at line {line}, column {column}: {message}")]
+    InvalidFrontmatter {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Frontmatter on line {line} must be a list of `key: value` pairs.")]
+    NotAMapping { line: usize },
+
+    #[error("Could not write frontmatter: {0}")]
+    Serialize(String),
+}
+
+/// A Markdown file split into YAML frontmatter and body.
+///
+/// Parsing keeps the original text, so serializing a document writes back
+/// exactly what was read: the body is never reformatted, and frontmatter
+/// entries whose value did not change keep their original formatting and
+/// comments. Only added or edited entries are re-rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    frontmatter: Mapping,
+    body: String,
+    source: Option<SourceFrontmatter>,
+    line_ending: &'static str,
+    bom: bool,
+}
+
+/// The frontmatter exactly as it was read.
+#[derive(Debug, Clone, PartialEq)]
+struct SourceFrontmatter {
+    open_fence: String,
+    close_fence: String,
+    raw: String,
+    parsed: Mapping,
+    layout: Option<Layout>,
+}
+
+/// The raw frontmatter text split per top-level key. Absent when the text
+/// could not be split reliably, in which case edits re-render the whole map.
+#[derive(Debug, Clone, PartialEq)]
+struct Layout {
+    /// Comments and blank lines before the first key.
+    prefix: String,
+    entries: Vec<(Value, String)>,
+}
+
+impl Document {
+    pub fn new(frontmatter: Mapping, body: impl Into<String>) -> Self {
+        Self {
+            frontmatter,
+            body: body.into(),
+            source: None,
+            line_ending: "\n",
+            bom: false,
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, DocumentError> {
+        let (bom, text) = match source.strip_prefix(BOM) {
+            Some(rest) => (true, rest),
+            None => (false, source),
+        };
+
+        let Some((open_fence, after_open)) = split_line(text) else {
+            return Ok(Self::plain(text, bom));
+        };
+        if open_fence.trim_end() != FENCE || !open_fence.ends_with('\n') {
+            return Ok(Self::plain(text, bom));
+        }
+
+        let line_ending = if open_fence.ends_with("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        let mut raw_len = 0;
+        let mut rest = after_open;
+        let (close_fence, body) = loop {
+            let Some((line, after)) = split_line(rest) else {
+                return Err(DocumentError::UnclosedFrontmatter { line: 1 });
+            };
+
+            if CLOSING_FENCES.contains(&line.trim_end()) {
+                break (line, after);
+            }
+
+            raw_len += line.len();
+            rest = after;
+        };
+
+        let raw = &after_open[..raw_len];
+        let parsed = parse_frontmatter(raw)?;
+        let layout = Layout::split(raw, &parsed);
+
+        Ok(Self {
+            frontmatter: parsed.clone(),
+            body: body.to_string(),
+            source: Some(SourceFrontmatter {
+                open_fence: open_fence.to_string(),
+                close_fence: close_fence.to_string(),
+                raw: raw.to_string(),
+                parsed,
+                layout,
+            }),
+            line_ending,
+            bom,
+        })
+    }
+
+    fn plain(text: &str, bom: bool) -> Self {
+        Self {
+            bom,
+            ..Self::new(Mapping::new(), text)
+        }
+    }
+
+    pub fn frontmatter(&self) -> &Mapping {
+        &self.frontmatter
+    }
+
+    pub fn frontmatter_mut(&mut self) -> &mut Mapping {
+        &mut self.frontmatter
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.frontmatter.get(key)
+    }
+
+    /// Inserts or replaces a frontmatter entry. Existing keys keep their
+    /// position; new keys are appended.
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) -> Option<Value> {
+        self.frontmatter.insert(key.into(), value.into())
+    }
+
+    /// Removes a frontmatter entry, keeping the order of the others.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.frontmatter.shift_remove(key)
+    }
+
+    /// Whether the file had a frontmatter block or one will be written.
+    pub fn has_frontmatter(&self) -> bool {
+        self.source.is_some() || !self.frontmatter.is_empty()
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn set_body(&mut self, body: impl Into<String>) {
+        self.body = body.into();
+    }
+
+    /// Line in the file where the body starts, counting from 1.
+    pub fn body_line(&self) -> usize {
+        match &self.source {
+            // Opening fence, frontmatter lines, closing fence.
+            Some(source) => 3 + source.raw.matches('\n').count(),
+            None => 1,
+        }
+    }
+
+    /// Line in the file where a frontmatter key was written, counting from 1.
+    /// `None` for keys added since parsing or when the layout is unknown.
+    pub fn key_line(&self, key: &str) -> Option<usize> {
+        let layout = self.source.as_ref()?.layout.as_ref()?;
+
+        // The opening fence is line 1.
+        let mut line = 2 + layout.prefix.matches('\n').count();
+        for (entry_key, text) in &layout.entries {
+            if entry_key.as_str() == Some(key) {
+                return Some(line);
+            }
+            line += text.matches('\n').count();
+        }
+
+        None
+    }
+
+    pub fn to_markdown(&self) -> Result<String, DocumentError> {
+        let mut output = String::with_capacity(self.body.len() + 256);
+        if self.bom {
+            output.push(BOM);
+        }
+
+        match &self.source {
+            Some(source) => {
+                output.push_str(&source.open_fence);
+                output.push_str(&self.render_frontmatter(source)?);
+                output.push_str(&source.close_fence);
+            }
+            None if !self.frontmatter.is_empty() => {
+                output.push_str(FENCE);
+                output.push_str(self.line_ending);
+                output.push_str(&self.render_yaml(&self.frontmatter)?);
+                output.push_str(FENCE);
+                output.push_str(self.line_ending);
+            }
+            None => {}
+        }
+
+        output.push_str(&self.body);
+        Ok(output)
+    }
+
+    fn render_frontmatter(&self, source: &SourceFrontmatter) -> Result<String, DocumentError> {
+        if self.frontmatter == source.parsed {
+            return Ok(source.raw.clone());
+        }
+
+        let Some(layout) = &source.layout else {
+            return self.render_yaml(&self.frontmatter);
+        };
+
+        let mut output = layout.prefix.clone();
+        for (key, value) in &self.frontmatter {
+            let original = layout
+                .entries
+                .iter()
+                .find(|(original_key, _)| original_key == key)
+                .filter(|_| source.parsed.get(key) == Some(value));
+
+            match original {
+                Some((_, text)) => output.push_str(text),
+                None => {
+                    let mut entry = Mapping::new();
+                    entry.insert(key.clone(), value.clone());
+                    output.push_str(&self.render_yaml(&entry)?);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn render_yaml(&self, mapping: &Mapping) -> Result<String, DocumentError> {
+        if mapping.is_empty() {
+            return Ok(String::new());
+        }
+
+        let yaml = serde_yaml_ng::to_string(mapping)
+            .map_err(|error| DocumentError::Serialize(error.to_string()))?;
+
+        Ok(if self.line_ending == "\n" {
+            yaml
+        } else {
+            yaml.replace('\n', self.line_ending)
+        })
+    }
+}
+
+impl Layout {
+    /// A new top-level entry starts on any line that is not indented, a
+    /// comment, a block sequence item or the end of a flow collection.
+    fn split(raw: &str, parsed: &Mapping) -> Option<Self> {
+        let mut prefix = String::new();
+        let mut chunks: Vec<String> = Vec::new();
+
+        let mut rest = raw;
+        while let Some((line, after)) = split_line(rest) {
+            let starts_entry = line
+                .chars()
+                .next()
+                .is_some_and(|ch| !matches!(ch, ' ' | '\t' | '#' | '-' | ']' | '}' | '\r' | '\n'));
+
+            match chunks.last_mut() {
+                Some(chunk) if !starts_entry => chunk.push_str(line),
+                None if !starts_entry => prefix.push_str(line),
+                _ => chunks.push(line.to_string()),
+            }
+            rest = after;
+        }
+
+        if chunks.len() != parsed.len() {
+            return None;
+        }
+
+        let mut entries = Vec::with_capacity(chunks.len());
+        for (chunk, (key, value)) in chunks.into_iter().zip(parsed) {
+            let entry: Mapping = serde_yaml_ng::from_str(&chunk).ok()?;
+            if entry.len() != 1 || entry.get(key) != Some(value) {
+                return None;
+            }
+            entries.push((key.clone(), chunk));
+        }
+
+        Some(Self { prefix, entries })
+    }
+}
+
+fn parse_frontmatter(raw: &str) -> Result<Mapping, DocumentError> {
+    // The opening fence is line 1.
+    const FIRST_LINE: usize = 2;
+
+    let value: Value = serde_yaml_ng::from_str(raw).map_err(|error| {
+        let message = error.to_string();
+        match error.location() {
+            Some(location) => {
+                let suffix = format!(" at line {} column {}", location.line(), location.column());
+                DocumentError::InvalidFrontmatter {
+                    line: location.line() + FIRST_LINE - 1,
+                    column: location.column(),
+                    // The position is reported separately; any other position
+                    // in the message is made relative to the file as well.
+                    message: shift_line_numbers(&message.replacen(&suffix, "", 1), FIRST_LINE - 1),
+                }
+            }
+            None => DocumentError::InvalidFrontmatter {
+                line: FIRST_LINE,
+                column: 1,
+                message,
+            },
+        }
+    })?;
+
+    match value {
+        Value::Mapping(mapping) => Ok(mapping),
+        Value::Null => Ok(Mapping::new()),
+        _ => Err(DocumentError::NotAMapping { line: FIRST_LINE }),
+    }
+}
+
+/// Rewrites `line <n>` mentions in a parser message by `offset` lines.
+fn shift_line_numbers(message: &str, offset: usize) -> String {
+    const MARKER: &str = "line ";
+
+    let mut output = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find(MARKER) {
+        let (before, after) = rest.split_at(start + MARKER.len());
+        output.push_str(before);
+
+        let digits = after.len()
+            - after
+                .trim_start_matches(|ch: char| ch.is_ascii_digit())
+                .len();
+        match after[..digits].parse::<usize>() {
+            Ok(line) => output.push_str(&(line + offset).to_string()),
+            Err(_) => output.push_str(&after[..digits]),
+        }
+        rest = &after[digits..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Splits off the first line, including its line ending.
+fn split_line(text: &str) -> Option<(&str, &str)> {
+    if text.is_empty() {
+        return None;
+    }
+
+    let end = text.find('\n').map_or(text.len(), |index| index + 1);
+    Some(text.split_at(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Document, DocumentError, Mapping, Value};
+
+    const HERO: &str = "---
+# Core identity
+name: Maren Holt
+type: character
+aliases: [the Tide-Reader]   # kept as written
+home: \"[[Saltreach]]\"
+tags:
+- archivist
+- exile
+---
+# Maren Holt
+
+Keeper of the *Brine Archive*.
+";
+
+    #[test]
+    fn splits_frontmatter_and_body() {
+        let document = Document::parse(HERO).expect("parsed");
+
+        assert_eq!(
+            document.get("name"),
+            Some(&Value::String("Maren Holt".to_string()))
+        );
+        let keys: Vec<_> = document
+            .frontmatter()
+            .keys()
+            .filter_map(Value::as_str)
+            .collect();
+        assert_eq!(keys, ["name", "type", "aliases", "home", "tags"]);
+        assert!(document.body().starts_with("# Maren Holt\n"));
+        assert_eq!(document.body_line(), 11);
+        assert_eq!(document.key_line("name"), Some(3));
+        assert_eq!(document.key_line("tags"), Some(7));
+        assert_eq!(document.key_line("status"), None);
+    }
+
+    #[test]
+    fn round_trips_untouched_documents_byte_for_byte() {
+        let sources = [
+            HERO.to_string(),
+            HERO.replace('\n', "\r\n"),
+            format!("\u{feff}{HERO}"),
+            "No frontmatter here.\n---\nJust a rule.".to_string(),
+            "---\n---\n".to_string(),
+            "---\nname: Odd\n...\nBody".to_string(),
+        ];
+
+        for source in sources {
+            let document = Document::parse(&source).expect("parsed");
+            assert_eq!(document.to_markdown().expect("serialized"), source);
+        }
+    }
+
+    #[test]
+    fn rewrites_only_edited_entries() {
+        let mut document = Document::parse(HERO).expect("parsed");
+        document.set("name", "Maren Holt-Vell");
+        document.remove("type");
+        document.set("status", "active");
+
+        let output = document.to_markdown().expect("serialized");
+
+        assert_eq!(
+            output,
+            "---
+# Core identity
+name: Maren Holt-Vell
+aliases: [the Tide-Reader]   # kept as written
+home: \"[[Saltreach]]\"
+tags:
+- archivist
+- exile
+status: active
+---
+# Maren Holt
+
+Keeper of the *Brine Archive*.
+"
+        );
+    }
+
+    #[test]
+    fn adds_frontmatter_to_plain_documents() {
+        let mut document = Document::parse("Body only\r\n").expect("parsed");
+        assert!(!document.has_frontmatter());
+
+        document.set("type", "note");
+
+        assert_eq!(
+            document.to_markdown().expect("serialized"),
+            "---\ntype: note\n---\nBody only\r\n"
+        );
+
+        let mut frontmatter = Mapping::new();
+        frontmatter.insert("name".into(), "Saltreach".into());
+        let created = Document::new(frontmatter, "# Saltreach\n");
+        assert_eq!(
+            created.to_markdown().expect("serialized"),
+            "---\nname: Saltreach\n---\n# Saltreach\n"
+        );
+    }
+
+    #[test]
+    fn reports_frontmatter_errors_with_file_positions() {
+        let error = Document::parse("---\nname: Maren\nhome: [Saltreach\n---\nBody\n")
+            .expect_err("must fail");
+        let DocumentError::InvalidFrontmatter {
+            line,
+            column,
+            message,
+        } = error
+        else {
+            panic!("expected a located error, got {error:?}");
+        };
+        assert!(line >= 3, "line {line}");
+        assert!(column >= 1);
+        assert!(message.contains("at line 3 column"), "{message}");
+
+        let error =
+            Document::parse("---\nname: Maren\n  bad: indent\n---\n").expect_err("must fail");
+        assert!(matches!(
+            error,
+            DocumentError::InvalidFrontmatter { line: 3, .. }
+        ));
+
+        assert_eq!(
+            Document::parse("---\nname: Maren\n").expect_err("must fail"),
+            DocumentError::UnclosedFrontmatter { line: 1 }
+        );
+        assert_eq!(
+            Document::parse("---\n- a list\n---\n").expect_err("must fail"),
+            DocumentError::NotAMapping { line: 2 }
+        );
+    }
+}
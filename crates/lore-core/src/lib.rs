@@ -1,9 +1,13 @@
-//! Shared Rust workspace crate placeholder.
+//! Format-level building blocks shared by the workspace and app crates.
 //!
-//! This crate exists so the Cargo workspace can be rooted around `crates/*`
-//! from the beginning, even before the domain logic is extracted out of the
-//! Tauri application crate.
+//! Nothing here touches the filesystem: callers read and write files and hand
+//! the contents to these types.
 
-pub fn workspace_ready() -> bool {
-    true
-}
+mod document;
+mod links;
+mod query;
+
+pub use document::{Document, DocumentError};
+pub use links::{extract_links, WikiLink};
+pub use query::{CompareOp, Expr, Operand, Query, QueryError, SortKey};
+pub use serde_yaml_ng::{Mapping, Value};
@@ -0,0 +1,239 @@
+use std::ops::Range;
+
+const OPEN: &str = "[[";
+const CLOSE: &str = "]]";
+
+/// A `[[target#heading|alias]]` link, or a `![[...]]` embed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub target: String,
+    pub heading: Option<String>,
+    pub alias: Option<String>,
+    pub embed: bool,
+    /// Byte range of the whole link, brackets included, in the parsed text.
+    pub span: Range<usize>,
+    /// Position of the link in the parsed text, counting from 1. Columns
+    /// count characters.
+    pub line: usize,
+    pub column: usize,
+}
+
+impl WikiLink {
+    /// Parses a value that is exactly one link, such as `"[[Saltreach]]"`
+    /// in frontmatter. Surrounding whitespace is ignored.
+    pub fn parse(value: &str) -> Option<Self> {
+        let trimmed = value.trim_start();
+        let offset = value.len() - trimmed.len();
+        let trimmed = trimmed.trim_end();
+
+        let (embed, rest) = match trimmed.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let inner = rest.strip_prefix(OPEN)?.strip_suffix(CLOSE)?;
+
+        Self::from_inner(inner, embed, offset..offset + trimmed.len(), 1, 1 + offset)
+    }
+
+    fn from_inner(
+        inner: &str,
+        embed: bool,
+        span: Range<usize>,
+        line: usize,
+        column: usize,
+    ) -> Option<Self> {
+        if inner.contains(['[', ']', '\n']) {
+            return None;
+        }
+
+        let (reference, alias) = match inner.split_once('|') {
+            Some((reference, alias)) => (reference, Some(alias.trim())),
+            None => (inner, None),
+        };
+        let (target, heading) = match reference.split_once('#') {
+            Some((target, heading)) => (target, Some(heading.trim())),
+            None => (reference, None),
+        };
+        let target = target.trim();
+        if target.is_empty() {
+            return None;
+        }
+
+        let non_empty =
+            |text: Option<&str>| text.filter(|text| !text.is_empty()).map(str::to_string);
+        Some(Self {
+            target: target.to_string(),
+            heading: non_empty(heading),
+            alias: non_empty(alias),
+            embed,
+            span,
+            line,
+            column,
+        })
+    }
+
+    /// The text shown for the link: its alias, or else its target.
+    pub fn label(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.target)
+    }
+}
+
+/// Finds the wiki-links in Markdown text, skipping fenced code blocks and
+/// inline code spans.
+pub fn extract_links(text: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut line_start = 0;
+
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let offset = line_start;
+        line_start += line.len();
+
+        if let Some((fence_char, fence_len)) = code_fence(line) {
+            match fence {
+                None => {
+                    fence = Some((fence_char, fence_len));
+                    continue;
+                }
+                Some((open_char, open_len)) if open_char == fence_char && fence_len >= open_len => {
+                    fence = None;
+                    continue;
+                }
+                Some(_) => {}
+            }
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        extract_from_line(line, offset, line_number, &mut links);
+    }
+
+    links
+}
+
+fn extract_from_line(line: &str, offset: usize, line_number: usize, links: &mut Vec<WikiLink>) {
+    let bytes = line.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'`' => {
+                let run = bytes[index..]
+                    .iter()
+                    .take_while(|&&byte| byte == b'`')
+                    .count();
+                let closing = "`".repeat(run);
+                index = match line[index + run..].find(&closing) {
+                    Some(end) => index + run + end + run,
+                    // An unmatched run is literal text.
+                    None => index + run,
+                };
+            }
+            b'[' if line[index..].starts_with(OPEN) => {
+                let embed = index > 0 && bytes[index - 1] == b'!';
+                let start = if embed { index - 1 } else { index };
+                let inner_start = index + OPEN.len();
+
+                let link = line[inner_start..].find(CLOSE).and_then(|end| {
+                    let end = inner_start + end + CLOSE.len();
+                    let column = line[..start].chars().count() + 1;
+                    WikiLink::from_inner(
+                        &line[inner_start..end - CLOSE.len()],
+                        embed,
+                        offset + start..offset + end,
+                        line_number,
+                        column,
+                    )
+                });
+
+                match link {
+                    Some(link) => {
+                        index = link.span.end - offset;
+                        links.push(link);
+                    }
+                    None => index += OPEN.len(),
+                }
+            }
+            _ => index += 1,
+        }
+    }
+}
+
+/// The fence character and length when `line` opens or closes a fenced code
+/// block.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+
+    let rest = &line[indent..];
+    let fence_char = rest.chars().next().filter(|ch| matches!(ch, '`' | '~'))?;
+    let fence_len = rest.chars().take_while(|&ch| ch == fence_char).count();
+
+    (fence_len >= 3).then_some((fence_char, fence_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_links, WikiLink};
+
+    #[test]
+    fn parses_targets_headings_and_aliases() {
+        let link = WikiLink::parse(" [[Capital#Harbour|the capital]] ").expect("link");
+
+        assert_eq!(link.target, "Capital");
+        assert_eq!(link.heading.as_deref(), Some("Harbour"));
+        assert_eq!(link.alias.as_deref(), Some("the capital"));
+        assert_eq!(link.label(), "the capital");
+        assert_eq!(link.span, 1..32);
+        assert!(!link.embed);
+
+        assert!(WikiLink::parse("![[map.png]]").expect("embed").embed);
+        assert_eq!(WikiLink::parse("Saltreach"), None);
+        assert_eq!(WikiLink::parse("[[ |alias]]"), None);
+        assert_eq!(WikiLink::parse("[[a]] and [[b]]"), None);
+    }
+
+    #[test]
+    fn extracts_links_with_positions() {
+        let text = "Met [[Elandra Vosh]] in\n[[Capital|the capital]], née [[Vosh]].";
+
+        let links = extract_links(text);
+
+        let found: Vec<_> = links
+            .iter()
+            .map(|link| (link.target.as_str(), link.line, link.column))
+            .collect();
+        assert_eq!(
+            found,
+            [("Elandra Vosh", 1, 5), ("Capital", 2, 1), ("Vosh", 2, 30)]
+        );
+        assert_eq!(&text[links[1].span.clone()], "[[Capital|the capital]]");
+    }
+
+    #[test]
+    fn skips_code_and_malformed_links() {
+        let text = "\
+`[[Inline]]` and \\[[Escaped]] and [[Open
+```
+[[Fenced]]
+```
+~~~~
+[[Tilde]]
+~~~
+[[Still fenced]]
+~~~~
+[[Real]] [[]] [[a[b]]";
+
+        let targets: Vec<_> = extract_links(text)
+            .into_iter()
+            .map(|link| link.target)
+            .collect();
+
+        assert_eq!(targets, ["Real"]);
+    }
+}
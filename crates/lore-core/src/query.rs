@@ -0,0 +1,486 @@
+use thiserror::Error;
+
+use crate::links::WikiLink;
+
+/// A query over frontmatter fields, such as
+/// `type = character AND faction -> [[Iron Court]] SORT name DESC LIMIT 10`.
+///
+/// The filter combines comparisons with `AND`, `OR`, `NOT` and parentheses;
+/// a field on its own matches documents where it is set. Keywords ignore
+/// case. Values are bare words, `"quoted text"` or `[[links]]`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Query {
+    /// `None` matches every document.
+    pub filter: Option<Expr>,
+    pub sort: Vec<SortKey>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Exists(String),
+    Compare {
+        field: String,
+        op: CompareOp,
+        value: Operand,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// `->`: the field links to the given document.
+    LinksTo,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Text(String),
+    /// The target of a `[[link]]`.
+    Link(String),
+}
+
+impl Operand {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Link(text) => text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: String,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (column {column})")]
+pub struct QueryError {
+    pub message: String,
+    /// Counting characters from 1.
+    pub column: usize,
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(source)?;
+        Parser {
+            tokens,
+            position: 0,
+            end: source.chars().count() + 1,
+        }
+        .query()
+    }
+
+    /// Every field the query mentions, in order of first appearance.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        if let Some(filter) = &self.filter {
+            filter.collect_fields(&mut fields);
+        }
+        for key in &self.sort {
+            if !fields.contains(&key.field.as_str()) {
+                fields.push(&key.field);
+            }
+        }
+        fields
+    }
+}
+
+impl Expr {
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            Self::Not(inner) => inner.collect_fields(fields),
+            Self::Exists(field) | Self::Compare { field, .. } => {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Link(String),
+    Op(CompareOp),
+    Open,
+    Close,
+    Comma,
+}
+
+impl Token {
+    fn keyword(&self) -> Option<String> {
+        match self {
+            Self::Word(word) => Some(word.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "AND", "OR", "NOT", "SORT", "ASC", "DESC", "LIMIT", "CONTAINS",
+];
+
+/// Tokens with the column each starts at.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let column = index + 1;
+        let rest = &chars[index..];
+        let (token, len) = match rest {
+            [ch, ..] if ch.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            ['(', ..] => (Token::Open, 1),
+            [')', ..] => (Token::Close, 1),
+            [',', ..] => (Token::Comma, 1),
+            ['-', '>', ..] => (Token::Op(CompareOp::LinksTo), 2),
+            ['!', '=', ..] => (Token::Op(CompareOp::Ne), 2),
+            ['<', '=', ..] => (Token::Op(CompareOp::Le), 2),
+            ['>', '=', ..] => (Token::Op(CompareOp::Ge), 2),
+            ['=', '=', ..] => (Token::Op(CompareOp::Eq), 2),
+            ['=', ..] => (Token::Op(CompareOp::Eq), 1),
+            ['<', ..] => (Token::Op(CompareOp::Lt), 1),
+            ['>', ..] => (Token::Op(CompareOp::Gt), 1),
+            ['"', ..] => quoted(rest, column)?,
+            ['[', '[', ..] => link(rest, column)?,
+            _ => {
+                let len = rest
+                    .iter()
+                    .enumerate()
+                    .take_while(|&(offset, &ch)| {
+                        let starts_arrow = ch == '-' && rest.get(offset + 1) == Some(&'>');
+                        !ch.is_whitespace() && !"()=!<>,\"".contains(ch) && !starts_arrow
+                    })
+                    .count();
+                if len == 0 {
+                    return Err(error(format!("Unexpected '{}'", rest[0]), column));
+                }
+                (Token::Word(rest[..len].iter().collect()), len)
+            }
+        };
+        tokens.push((token, column));
+        index += len;
+    }
+
+    Ok(tokens)
+}
+
+/// A `"..."` string in which `\"` and `\\` are escapes.
+fn quoted(chars: &[char], column: usize) -> Result<(Token, usize), QueryError> {
+    let mut text = String::new();
+    let mut index = 1;
+    while let Some(&ch) = chars.get(index) {
+        match ch {
+            '"' => return Ok((Token::Quoted(text), index + 1)),
+            '\\' if chars.get(index + 1).is_some() => {
+                text.push(chars[index + 1]);
+                index += 2;
+            }
+            _ => {
+                text.push(ch);
+                index += 1;
+            }
+        }
+    }
+    Err(error("Unclosed quote", column))
+}
+
+fn link(chars: &[char], column: usize) -> Result<(Token, usize), QueryError> {
+    let text: String = chars.iter().collect();
+    let Some(end) = text.find("]]") else {
+        return Err(error("Unclosed link", column));
+    };
+    let source = &text[..end + 2];
+    let link = WikiLink::parse(source).ok_or_else(|| error("Invalid link", column))?;
+    Ok((Token::Link(link.target), source.chars().count()))
+}
+
+fn error(message: impl Into<String>, column: usize) -> QueryError {
+    QueryError {
+        message: message.into(),
+        column,
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    /// Column reported for errors at the end of the input.
+    end: usize,
+}
+
+impl Parser {
+    fn query(mut self) -> Result<Query, QueryError> {
+        let mut query = Query::default();
+
+        if self.peek().is_some() && !self.at_keyword("SORT") && !self.at_keyword("LIMIT") {
+            query.filter = Some(self.or()?);
+        }
+        if self.eat_keyword("SORT") {
+            loop {
+                let field = self.field("a field to sort by")?;
+                let descending = if self.eat_keyword("DESC") {
+                    true
+                } else {
+                    self.eat_keyword("ASC");
+                    false
+                };
+                query.sort.push(SortKey { field, descending });
+                if !self.eat(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        if self.eat_keyword("LIMIT") {
+            let column = self.column();
+            query.limit = match self.next() {
+                Some(Token::Word(word)) => word.parse().ok(),
+                _ => None,
+            };
+            if query.limit.is_none() {
+                return Err(error("Expected a number after LIMIT", column));
+            }
+        }
+
+        match self.peek() {
+            None => Ok(query),
+            Some(token) => Err(error(
+                format!("Unexpected {}", describe(token)),
+                self.column(),
+            )),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while self.eat_keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.not()?;
+        while self.eat_keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        if self.eat(&Token::Open) {
+            let expr = self.or()?;
+            if !self.eat(&Token::Close) {
+                return Err(error("Expected ')'", self.column()));
+            }
+            return Ok(expr);
+        }
+
+        let field = self.field("a field")?;
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            Some(token) if token.keyword().as_deref() == Some("CONTAINS") => CompareOp::Contains,
+            _ => return Ok(Expr::Exists(field)),
+        };
+        self.position += 1;
+
+        let column = self.column();
+        let value = match self.next() {
+            Some(Token::Word(word)) if !is_keyword(&word) => Operand::Text(word),
+            Some(Token::Quoted(text)) => Operand::Text(text),
+            Some(Token::Link(target)) => Operand::Link(target),
+            _ => return Err(error("Expected a value", column)),
+        };
+
+        Ok(Expr::Compare { field, op, value })
+    }
+
+    fn field(&mut self, expected: &str) -> Result<String, QueryError> {
+        let column = self.column();
+        match self.next() {
+            Some(Token::Word(word)) if !is_keyword(&word) => Ok(word),
+            Some(Token::Quoted(text)) if !text.is_empty() => Ok(text),
+            Some(token) => Err(error(
+                format!("Expected {expected}, found {}", describe(&token)),
+                column,
+            )),
+            None => Err(error(format!("Expected {expected}"), column)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.end, |&(_, column)| column)
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        let matched = self.peek() == Some(expected);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        self.peek().and_then(Token::keyword).as_deref() == Some(keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.at_keyword(keyword);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(&word.to_ascii_uppercase().as_str())
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{word}'"),
+        Token::Quoted(text) => format!("\"{text}\""),
+        Token::Link(target) => format!("[[{target}]]"),
+        Token::Op(_) => "an operator".to_string(),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+        Token::Comma => "','".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompareOp, Expr, Operand, Query, SortKey};
+
+    fn compare(field: &str, op: CompareOp, value: Operand) -> Expr {
+        Expr::Compare {
+            field: field.to_string(),
+            op,
+            value,
+        }
+    }
+
+    #[test]
+    fn parses_filters_sorting_and_limits() {
+        let query = Query::parse(
+            "type = character and status = active AND faction -> [[Iron Court|court]] sort name, age desc LIMIT 5",
+        )
+        .expect("query");
+
+        let expected = Expr::And(
+            Box::new(Expr::And(
+                Box::new(compare(
+                    "type",
+                    CompareOp::Eq,
+                    Operand::Text("character".into()),
+                )),
+                Box::new(compare(
+                    "status",
+                    CompareOp::Eq,
+                    Operand::Text("active".into()),
+                )),
+            )),
+            Box::new(compare(
+                "faction",
+                CompareOp::LinksTo,
+                Operand::Link("Iron Court".into()),
+            )),
+        );
+        assert_eq!(query.filter, Some(expected));
+        assert_eq!(
+            query.sort,
+            [
+                SortKey {
+                    field: "name".into(),
+                    descending: false
+                },
+                SortKey {
+                    field: "age".into(),
+                    descending: true
+                },
+            ]
+        );
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(query.fields(), ["type", "status", "faction", "name", "age"]);
+    }
+
+    #[test]
+    fn binds_not_and_parentheses() {
+        let query = Query::parse("NOT retired OR (age>=40 AND title contains \"the \\\"Red\\\"\")")
+            .expect("query");
+
+        let expected = Expr::Or(
+            Box::new(Expr::Not(Box::new(Expr::Exists("retired".into())))),
+            Box::new(Expr::And(
+                Box::new(compare("age", CompareOp::Ge, Operand::Text("40".into()))),
+                Box::new(compare(
+                    "title",
+                    CompareOp::Contains,
+                    Operand::Text("the \"Red\"".into()),
+                )),
+            )),
+        );
+        assert_eq!(query.filter, Some(expected));
+        assert_eq!(Query::parse("  ").expect("empty"), Query::default());
+        assert_eq!(Query::parse("SORT name").expect("sort only").filter, None);
+    }
+
+    #[test]
+    fn reports_errors_with_columns() {
+        let failures = [
+            ("type =", "Expected a value (column 7)"),
+            ("(type = a", "Expected ')' (column 10)"),
+            ("status = \"on hold", "Unclosed quote (column 10)"),
+            ("type = a b", "Unexpected 'b' (column 10)"),
+            ("AND x", "Expected a field, found 'AND' (column 1)"),
+            ("x LIMIT many", "Expected a number after LIMIT (column 9)"),
+        ];
+
+        for (source, message) in failures {
+            let error = Query::parse(source).expect_err(source);
+            assert_eq!(error.to_string(), message, "{source}");
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, Utc};
+use lore_core::{Document, DocumentError, Mapping, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::write_atomic,
+    index::IndexedDocument,
+    links::LinkReference,
+    registry::{CACHE_DIR, INTERNAL_DIR, WorkspaceError},
+    scan::scan_workspace,
+    schema::EntityTypes,
+};
+
+/// Parsed metadata of every Markdown file, in `.lore/cache/`. It only saves
+/// work: deleting it, or any error reading it, means files are parsed again.
+const METADATA_FILE: &str = "metadata.json";
+/// Bump when [`CachedFile`] or how its fields are computed changes.
+const METADATA_FORMAT_VERSION: u32 = 1;
+
+/// What refreshing the metadata cache did.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataRefresh {
+    /// Markdown files in the workspace.
+    pub files: usize,
+    /// Files that were new or changed, and had to be parsed.
+    pub parsed: usize,
+    /// Cached files that no longer exist.
+    pub removed: usize,
+    /// Total words in document bodies.
+    pub words: usize,
+}
+
+/// Brings the metadata cache of the workspace at `root` up to date, parsing
+/// only the files whose size or modification time changed.
+pub fn refresh_metadata_cache(root: impl AsRef<Path>) -> Result<MetadataRefresh, WorkspaceError> {
+    let root = root.as_ref();
+    let root = root
+        .canonicalize()
+        .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+    let types = EntityTypes::load(&root)?;
+    Ok(MetadataCache::load(&root, &types)?.refresh)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    version: u32,
+    /// The entity types the links were found with; link fields decide
+    /// which bare names are links.
+    types: String,
+    files: Vec<CachedFile>,
+}
+
+/// One Markdown file as of its last parse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CachedFile {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    size: u64,
+    modified_at: Option<DateTime<Utc>>,
+    pub frontmatter: Result<Mapping, DocumentError>,
+    /// Not yet resolved: resolution depends on the other files.
+    pub links: Vec<LinkReference>,
+    pub word_count: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct MetadataCache {
+    /// In the order of [`WorkspaceTree::files`](crate::WorkspaceTree).
+    pub files: Vec<CachedFile>,
+    pub refresh: MetadataRefresh,
+}
+
+impl MetadataCache {
+    /// Reads the cache, re-parses what changed and writes it back when
+    /// anything did. `root` should be canonical.
+    pub fn load(root: &Path, types: &EntityTypes) -> Result<Self, WorkspaceError> {
+        let cache_path = root.join(INTERNAL_DIR).join(CACHE_DIR).join(METADATA_FILE);
+        let types_key = serde_json::to_string(&types.types).unwrap_or_default();
+        let previous = read_cache(&cache_path, &types_key);
+        let mut dirty = previous.is_none();
+        let mut cached: HashMap<String, CachedFile> = previous
+            .into_iter()
+            .flatten()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+
+        let tree = scan_workspace(root)?;
+        let mut refresh = MetadataRefresh::default();
+        let mut files = Vec::new();
+        for entry in tree.files() {
+            if !entry.kind.is_markdown() {
+                continue;
+            }
+
+            let previous = cached.remove(&entry.path);
+            let file = match previous {
+                Some(file)
+                    if file.size == entry.size
+                        && file.modified_at.is_some()
+                        && file.modified_at == entry.modified_at =>
+                {
+                    file
+                }
+                _ => {
+                    // Files that vanished or are not UTF-8 are skipped, not fatal.
+                    let Ok(content) = fs::read_to_string(root.join(&entry.path)) else {
+                        continue;
+                    };
+                    refresh.parsed += 1;
+                    let file =
+                        parse_file(types, &entry.path, &content, entry.size, entry.modified_at);
+                    dirty |= previous.is_some() || is_cacheable(&file);
+                    file
+                }
+            };
+
+            refresh.words += file.word_count;
+            files.push(file);
+        }
+        refresh.files = files.len();
+        refresh.removed = cached.len();
+        dirty |= refresh.removed > 0;
+
+        if dirty {
+            let cacheable = files.iter().filter(|file| is_cacheable(file)).cloned();
+            let cache = CacheFile {
+                version: METADATA_FORMAT_VERSION,
+                types: types_key,
+                files: cacheable.collect(),
+            };
+            // The cache only saves work, so failing to write it is not an error.
+            if let Ok(json) = serde_json::to_vec(&cache)
+                && let Some(dir) = cache_path.parent()
+                && fs::create_dir_all(dir).is_ok()
+            {
+                let _ = write_atomic(&cache_path, &json);
+            }
+        }
+
+        Ok(Self { files, refresh })
+    }
+}
+
+/// The cached files, or `None` when the cache is missing, corrupt, from
+/// another format version or built with other entity types.
+fn read_cache(path: &Path, types_key: &str) -> Option<Vec<CachedFile>> {
+    let bytes = fs::read(path).ok()?;
+    let cache: CacheFile = serde_json::from_slice(&bytes).ok()?;
+    (cache.version == METADATA_FORMAT_VERSION && cache.types == types_key).then_some(cache.files)
+}
+
+fn parse_file(
+    types: &EntityTypes,
+    path: &str,
+    content: &str,
+    size: u64,
+    modified_at: Option<DateTime<Utc>>,
+) -> CachedFile {
+    let document = IndexedDocument {
+        path: path.to_string(),
+        document: Document::parse(content),
+        links: None,
+    };
+    let links = document.find_links(types);
+    let body = document.parsed().map_or(content, Document::body);
+
+    CachedFile {
+        path: path.to_string(),
+        size,
+        modified_at,
+        word_count: count_words(body),
+        frontmatter: document
+            .document
+            .map(|document| document.frontmatter().clone()),
+        links,
+    }
+}
+
+/// Words are runs of non-whitespace holding at least one letter or digit,
+/// so Markdown markers such as `#` or `-` do not count.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// JSON cannot hold every YAML value faithfully: such files are left out
+/// of the cache and parsed every time.
+fn is_cacheable(file: &CachedFile) -> bool {
+    fn is_plain(value: &Value) -> bool {
+        match value {
+            Value::Null | Value::Bool(_) | Value::String(_) => true,
+            Value::Number(number) => number.as_f64().is_some_and(f64::is_finite),
+            Value::Sequence(items) => items.iter().all(is_plain),
+            Value::Mapping(mapping) => is_plain_mapping(mapping),
+            Value::Tagged(_) => false,
+        }
+    }
+    fn is_plain_mapping(mapping: &Mapping) -> bool {
+        mapping
+            .iter()
+            .all(|(key, value)| key.is_string() && is_plain(value))
+    }
+
+    file.frontmatter.as_ref().is_ok_and(is_plain_mapping) || file.frontmatter.is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread, time::Duration};
+
+    use tempfile::tempdir;
+
+    use super::{METADATA_FILE, refresh_metadata_cache};
+    use crate::index::WorkspaceIndex;
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters")).expect("create characters");
+        fs::write(
+            root.join("Characters/Elandra.character.md"),
+            "---\nname: Elandra Vosh\nhome: Saltreach\n---\n# Youth\n\nGrew up in [[Saltreach]].\n",
+        )
+        .expect("write elandra");
+        fs::write(
+            root.join("Saltreach.location.md"),
+            "A cold - harbour town.\n",
+        )
+        .expect("write saltreach");
+        temp
+    }
+
+    #[test]
+    fn parses_only_changed_files() {
+        let temp = workspace();
+        let root = temp.path();
+
+        let first = refresh_metadata_cache(root).expect("first refresh");
+        assert_eq!((first.files, first.parsed, first.words), (2, 2, 9));
+        assert!(root.join(".lore/cache").join(METADATA_FILE).is_file());
+
+        let second = refresh_metadata_cache(root).expect("second refresh");
+        assert_eq!((second.files, second.parsed, second.removed), (2, 0, 0));
+
+        // Size alone tells this edit apart, whatever the clock resolution.
+        thread::sleep(Duration::from_millis(10));
+        fs::write(root.join("Saltreach.location.md"), "A harbour.\n").expect("edit");
+        fs::remove_file(root.join("Characters/Elandra.character.md")).expect("remove");
+        fs::write(root.join("Oskar.md"), "New.\n").expect("add");
+
+        let third = refresh_metadata_cache(root).expect("third refresh");
+        assert_eq!(
+            (third.files, third.parsed, third.removed, third.words),
+            (2, 2, 1, 3)
+        );
+    }
+
+    #[test]
+    fn cached_index_matches_a_full_parse() {
+        let temp = workspace();
+        let root = temp.path().canonicalize().expect("canonical root");
+        refresh_metadata_cache(&root).expect("warm cache");
+
+        let full = WorkspaceIndex::load(&root).expect("full index");
+        let cached = WorkspaceIndex::load_cached(&root).expect("cached index");
+
+        let links = |index: &WorkspaceIndex| {
+            index
+                .documents
+                .iter()
+                .flat_map(|document| index.links(document))
+                .collect::<Vec<_>>()
+        };
+        let found = links(&cached);
+        assert_eq!(found, links(&full));
+        // `home` is a link field of characters, so its bare name counts.
+        let targets: Vec<_> = found
+            .iter()
+            .map(|link| (link.field.as_deref(), link.resolved_path.as_deref()))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                (Some("home"), Some("Saltreach.location.md")),
+                (None, Some("Saltreach.location.md"))
+            ]
+        );
+        let frontmatter = |index: &WorkspaceIndex| {
+            index
+                .documents
+                .iter()
+                .map(|document| document.parsed().map(|parsed| parsed.frontmatter().clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(frontmatter(&cached), frontmatter(&full));
+    }
+
+    #[test]
+    fn leaves_valid_caches_untouched() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::write(root.join("notes.txt"), "Not Markdown.\n").expect("write notes");
+        refresh_metadata_cache(root).expect("first refresh");
+        let cache_path = root.join(".lore/cache").join(METADATA_FILE);
+
+        // Saved caches are compact, so a pretty one shows it was not rewritten.
+        let cache: serde_json::Value =
+            serde_json::from_slice(&fs::read(&cache_path).expect("read cache")).expect("json");
+        let pretty = serde_json::to_string_pretty(&cache).expect("pretty");
+        fs::write(&cache_path, &pretty).expect("write pretty");
+
+        let refresh = refresh_metadata_cache(root).expect("second refresh");
+
+        assert_eq!((refresh.files, refresh.parsed), (0, 0));
+        assert_eq!(fs::read_to_string(&cache_path).expect("reread"), pretty);
+    }
+
+    #[test]
+    fn discards_corrupt_or_outdated_caches() {
+        let temp = workspace();
+        let root = temp.path();
+        refresh_metadata_cache(root).expect("warm cache");
+        let cache_path = root.join(".lore/cache").join(METADATA_FILE);
+
+        fs::write(&cache_path, "{\"version\": 1, \"files\": [").expect("corrupt");
+        assert_eq!(refresh_metadata_cache(root).expect("corrupt").parsed, 2);
+
+        let outdated = fs::read_to_string(&cache_path)
+            .expect("read cache")
+            .replacen("\"version\":1", "\"version\":0", 1);
+        fs::write(&cache_path, outdated).expect("outdated");
+        assert_eq!(refresh_metadata_cache(root).expect("outdated").parsed, 2);
+
+        // New entity types change which bare names are links.
+        fs::create_dir_all(root.join(".lore/types")).expect("create types");
+        fs::write(
+            root.join(".lore/types/faction.toml"),
+            "id = \"faction\"\ndisplay_name = \"Faction\"\n",
+        )
+        .expect("write type");
+        assert_eq!(refresh_metadata_cache(root).expect("new types").parsed, 2);
+        assert_eq!(refresh_metadata_cache(root).expect("warm again").parsed, 0);
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use lore_core::{Document, Mapping};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    registry::{INTERNAL_DIR, WorkspaceError},
+    scan::relative_path,
+};
+
+/// Suffix of the temporary files written next to a document while saving.
+/// The workspace filter always ignores them.
+pub(crate) const TEMP_FILE_SUFFIX: &str = ".lore-tmp";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedDocument {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub content: String,
+    /// Empty when the file has no frontmatter or it could not be parsed.
+    pub frontmatter: Mapping,
+    pub body: String,
+    pub frontmatter_error: Option<String>,
+    /// Pass back to [`save_document`] to detect changes made on disk since.
+    pub hash: String,
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+pub fn load_document(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<LoadedDocument, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(WorkspaceError::DocumentNotFound(relative_path(
+                &root, &path,
+            )));
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    loaded_document(&root, &path, content)
+}
+
+/// Writes `content` atomically. When `expected_hash` is given, the save is
+/// refused with [`WorkspaceError::DocumentConflict`] if the file on disk no
+/// longer matches it; pass `None` to create a file or overwrite regardless.
+pub fn save_document(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+    content: &str,
+    expected_hash: Option<&str>,
+) -> Result<LoadedDocument, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+
+    if let Some(expected_hash) = expected_hash {
+        let current_hash = match fs::read(&path) {
+            Ok(current) => Some(content_hash(&current)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        if current_hash.as_deref() != Some(expected_hash) {
+            return Err(WorkspaceError::DocumentConflict(relative_path(
+                &root, &path,
+            )));
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(&path, content.as_bytes())?;
+
+    loaded_document(&root, &path, content.to_string())
+}
+
+fn loaded_document(
+    root: &Path,
+    path: &Path,
+    content: String,
+) -> Result<LoadedDocument, WorkspaceError> {
+    let (frontmatter, body, frontmatter_error) = match Document::parse(&content) {
+        Ok(document) => (
+            document.frontmatter().clone(),
+            document.body().to_string(),
+            None,
+        ),
+        Err(error) => (Mapping::new(), content.clone(), Some(error.to_string())),
+    };
+
+    let modified_at = fs::metadata(path)?
+        .modified()
+        .ok()
+        .map(DateTime::<Utc>::from);
+
+    Ok(LoadedDocument {
+        path: relative_path(root, path),
+        hash: content_hash(content.as_bytes()),
+        content,
+        frontmatter,
+        body,
+        frontmatter_error,
+        modified_at,
+    })
+}
+
+/// Resolves `path` (absolute, or relative to `root`) and makes sure it stays
+/// inside the workspace, following symlinks, and outside `.lore/`. Returns
+/// the canonical root and the resolved path.
+pub(crate) fn resolve_workspace_path(
+    root: &Path,
+    path: &Path,
+) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+    let (root, resolved) = resolve_path(root, path)?;
+    check_inside(path, root, resolved)
+}
+
+/// Like [`resolve_workspace_path`], except that a symlink at `path` itself
+/// is not followed, only its folder is resolved. For acting on the link.
+pub(crate) fn resolve_workspace_entry(
+    root: &Path,
+    path: &Path,
+) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+    let Some(Component::Normal(name)) = path.components().next_back() else {
+        return Err(WorkspaceError::PathOutsideWorkspace(
+            path.display().to_string(),
+        ));
+    };
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let (root, folder) = resolve_path(root, parent)?;
+    check_inside(path, root, folder.join(name))
+}
+
+/// The canonical root and `path` resolved against it, following symlinks.
+fn resolve_path(root: &Path, path: &Path) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+    let outside = || WorkspaceError::PathOutsideWorkspace(path.display().to_string());
+
+    let root = root
+        .canonicalize()
+        .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(outside());
+                }
+            }
+            Component::CurDir => {}
+            component => normalized.push(component),
+        }
+    }
+
+    // Canonicalize the deepest existing ancestor so symlinks cannot escape.
+    let mut existing = normalized.as_path();
+    let mut missing = Vec::new();
+    let resolved = loop {
+        match existing.canonicalize() {
+            Ok(canonical) => break canonical,
+            Err(_) => {
+                missing.push(existing.file_name().ok_or_else(outside)?);
+                existing = existing.parent().ok_or_else(outside)?;
+            }
+        }
+    };
+    let resolved = missing
+        .iter()
+        .rev()
+        .fold(resolved, |resolved, name| resolved.join(name));
+    Ok((root, resolved))
+}
+
+/// Refuses `resolved` unless it is below `root` and outside `.lore/`.
+fn check_inside(
+    path: &Path,
+    root: PathBuf,
+    resolved: PathBuf,
+) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+    let outside = || WorkspaceError::PathOutsideWorkspace(path.display().to_string());
+    let relative = resolved.strip_prefix(&root).map_err(|_| outside())?;
+    match relative.components().next() {
+        None => Err(outside()),
+        Some(first) if first.as_os_str() == INTERNAL_DIR => Err(outside()),
+        Some(_) => Ok((root, resolved)),
+    }
+}
+
+/// Writes to a temporary file beside `path`, flushes it to disk and renames
+/// it over `path`, so readers see either the old or the new content.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_string_lossy();
+    let temp_path = path.with_file_name(format!(
+        ".{file_name}.{}{TEMP_FILE_SUFFIX}",
+        Uuid::new_v4().simple()
+    ));
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Persist the rename itself; not supported for directories on Windows.
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        File::open(parent)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// FNV-1a, enough to notice that a file changed between load and save.
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{load_document, save_document};
+    use crate::registry::WorkspaceError;
+
+    const MAREN: &str = "---\nname: Maren Holt\n---\n# Maren\n";
+
+    #[test]
+    fn loads_frontmatter_and_body() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join("Characters")).expect("create folder");
+        fs::write(temp.path().join("Characters/Maren.character.md"), MAREN).expect("write");
+
+        let document = load_document(temp.path(), "Characters/Maren.character.md").expect("loaded");
+
+        assert_eq!(document.path, "Characters/Maren.character.md");
+        assert_eq!(
+            document
+                .frontmatter
+                .get("name")
+                .and_then(|value| value.as_str()),
+            Some("Maren Holt")
+        );
+        assert_eq!(document.body, "# Maren\n");
+        assert_eq!(document.content, MAREN);
+        assert!(document.frontmatter_error.is_none());
+    }
+
+    #[test]
+    fn reports_malformed_frontmatter_without_failing() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(
+            temp.path().join("Broken.md"),
+            "---\nname: [oops\n---\nBody\n",
+        )
+        .expect("write");
+
+        let document = load_document(temp.path(), "Broken.md").expect("loaded");
+
+        assert!(document.frontmatter.is_empty());
+        assert!(document.frontmatter_error.is_some());
+        assert_eq!(document.body, document.content);
+    }
+
+    #[test]
+    fn saves_atomically_and_returns_new_hash() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("Maren.md"), MAREN).expect("write");
+        let loaded = load_document(temp.path(), "Maren.md").expect("loaded");
+
+        let updated = MAREN.replace("Holt", "Vell");
+        let saved =
+            save_document(temp.path(), "Maren.md", &updated, Some(&loaded.hash)).expect("saved");
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("Maren.md")).expect("read"),
+            updated
+        );
+        assert_ne!(saved.hash, loaded.hash);
+        let leftovers = fs::read_dir(temp.path()).expect("read dir").count();
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn refuses_to_overwrite_external_changes() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("Maren.md"), MAREN).expect("write");
+        let loaded = load_document(temp.path(), "Maren.md").expect("loaded");
+        fs::write(temp.path().join("Maren.md"), "changed elsewhere").expect("external edit");
+
+        let error = save_document(temp.path(), "Maren.md", "mine", Some(&loaded.hash))
+            .expect_err("must conflict");
+
+        assert!(matches!(error, WorkspaceError::DocumentConflict(_)));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("Maren.md")).expect("read"),
+            "changed elsewhere"
+        );
+        save_document(temp.path(), "Maren.md", "mine", None).expect("forced save");
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_workspace() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().join("atlas");
+        fs::create_dir_all(root.join(".lore")).expect("create workspace");
+        fs::write(temp.path().join("secret.md"), "outside").expect("write");
+
+        for path in [
+            "../secret.md",
+            ".lore/settings.toml",
+            "Notes/../../secret.md",
+        ] {
+            let error = save_document(&root, path, "x", None).expect_err("must refuse");
+            assert!(
+                matches!(error, WorkspaceError::PathOutsideWorkspace(_)),
+                "{path}: {error:?}"
+            );
+        }
+
+        let absolute = temp.path().join("secret.md");
+        assert!(matches!(
+            load_document(&root, &absolute),
+            Err(WorkspaceError::PathOutsideWorkspace(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("secret.md")).expect("read"),
+            "outside"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_that_escape_the_workspace() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().join("atlas");
+        fs::create_dir_all(&root).expect("create workspace");
+        std::os::unix::fs::symlink(temp.path(), root.join("escape")).expect("symlink");
+
+        let error = save_document(&root, "escape/new.md", "x", None).expect_err("must refuse");
+
+        assert!(matches!(error, WorkspaceError::PathOutsideWorkspace(_)));
+        assert!(!temp.path().join("new.md").exists());
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use lore_core::{Document, Mapping, Value};
+
+use crate::{
+    documents::resolve_workspace_path,
+    placeholders::render_markdown_placeholders,
+    registry::{INTERNAL_DIR, WorkspaceError},
+    scan::relative_path,
+    schema::{EntitySchema, EntityTypes, TYPE_KEY},
+    settings::load_workspace_settings,
+};
+
+/// Per-type templates, `.lore/templates/<type id>.md`.
+pub(crate) const TEMPLATES_DIR: &str = "templates";
+
+const NAME_KEY: &str = "name";
+const DEFAULT_TEMPLATE: &str = "# {{name}}\n";
+
+/// Creates `<name>.<type>.md` in `folder`, or in the type's default folder,
+/// from the workspace template for the type (`.lore/templates/<type>.md`)
+/// when there is one. Returns the new file's path relative to `root`.
+pub fn create_entity(
+    root: impl AsRef<Path>,
+    entity_type: &str,
+    name: &str,
+    folder: Option<&str>,
+) -> Result<String, WorkspaceError> {
+    let root = root.as_ref();
+    let types = EntityTypes::load(root)?;
+    let schema = entity_schema(&types, entity_type)?;
+
+    let template_path = root
+        .join(INTERNAL_DIR)
+        .join(TEMPLATES_DIR)
+        .join(format!("{}.md", schema.id));
+    let template = match fs::read_to_string(&template_path) {
+        Ok(template) => template,
+        Err(_) if !template_path.is_file() => DEFAULT_TEMPLATE.to_string(),
+        Err(error) => return Err(error.into()),
+    };
+
+    write_entity(root, schema, name, folder, &template)
+}
+
+/// Like [`create_entity`], with the given template instead of the
+/// workspace's. Templates may use `{{name}}`, `{{type}}` and
+/// `{{created_at}}`; `type`, `name` and the schema's field defaults are added
+/// to the frontmatter unless the template sets them.
+pub fn create_entity_from_template(
+    root: impl AsRef<Path>,
+    entity_type: &str,
+    name: &str,
+    folder: Option<&str>,
+    template: &str,
+) -> Result<String, WorkspaceError> {
+    let root = root.as_ref();
+    let types = EntityTypes::load(root)?;
+    let schema = entity_schema(&types, entity_type)?;
+    write_entity(root, schema, name, folder, template)
+}
+
+/// The schema of `entity_type`. Ids name template files, so one that could
+/// point outside `.lore/templates/` is never a known type.
+fn entity_schema<'a>(
+    types: &'a EntityTypes,
+    entity_type: &str,
+) -> Result<&'a EntitySchema, WorkspaceError> {
+    types
+        .get(entity_type)
+        .filter(|schema| !schema.id.contains(['/', '\\']))
+        .ok_or_else(|| WorkspaceError::UnknownEntityType(entity_type.to_string()))
+}
+
+fn write_entity(
+    root: &Path,
+    schema: &EntitySchema,
+    name: &str,
+    folder: Option<&str>,
+    template: &str,
+) -> Result<String, WorkspaceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(WorkspaceError::EmptyEntityName);
+    }
+
+    // Unreadable settings should not stop entity creation.
+    let settings = load_workspace_settings(root).unwrap_or_default();
+    let folder = folder
+        .map(str::trim)
+        .filter(|folder| !folder.is_empty())
+        .or(settings.entity_folders.get(&schema.id).map(String::as_str))
+        .or(schema.folder.as_deref());
+    let (root, dir) = match folder {
+        Some(folder) => resolve_workspace_path(root, Path::new(folder))?,
+        None => {
+            let root = root
+                .canonicalize()
+                .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+            (root.clone(), root)
+        }
+    };
+    if dir.is_file() {
+        return Err(WorkspaceError::DestinationIsFile(relative_path(
+            &root, &dir,
+        )));
+    }
+    fs::create_dir_all(&dir)?;
+
+    let content = render_entity(schema, name, template)?;
+    let path = write_new_file(&dir, &entity_file_stem(name), &schema.extension, &content)?;
+
+    Ok(relative_path(&root, &path))
+}
+
+fn render_entity(
+    schema: &EntitySchema,
+    name: &str,
+    template: &str,
+) -> Result<String, WorkspaceError> {
+    let created_at = Utc::now().to_rfc3339();
+    let rendered = render_markdown_placeholders(template, |key| match key {
+        "name" => Some(name),
+        "type" => Some(schema.id.as_str()),
+        "created_at" => Some(created_at.as_str()),
+        _ => None,
+    });
+
+    // A template with broken frontmatter is used as the body of a new one.
+    let mut document = Document::parse(&rendered)
+        .unwrap_or_else(|_| Document::new(Mapping::new(), rendered.clone()));
+
+    let mut frontmatter = Mapping::new();
+    frontmatter.insert(TYPE_KEY.into(), schema.id.clone().into());
+    frontmatter.insert(NAME_KEY.into(), name.into());
+    for field in &schema.fields {
+        if let Some(default) = &field.default {
+            frontmatter.insert(field.name.as_str().into(), default.clone());
+        }
+    }
+    for (key, value) in frontmatter {
+        let key = key.as_str().unwrap_or_default();
+        if document.get(key).is_none_or(Value::is_null) {
+            document.set(key, value);
+        }
+    }
+
+    document
+        .to_markdown()
+        .map_err(|error| WorkspaceError::Io(io::Error::other(error)))
+}
+
+/// `name`, made safe to use as a file name: path separators and characters
+/// Windows rejects become `-`, runs of whitespace collapse to one space, and
+/// leading dots are dropped so the file is never hidden.
+fn entity_file_stem(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            ch if ch.is_control() => ' ',
+            ch => ch,
+        })
+        .collect();
+    let collapsed = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stem = collapsed
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+
+    if stem.is_empty() {
+        "Untitled".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Creates `<stem>.<extension>.md` in `dir`, or `<stem> 2.<extension>.md`
+/// and so on when taken. Never overwrites an existing file.
+fn write_new_file(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+    content: &str,
+) -> Result<PathBuf, WorkspaceError> {
+    for attempt in 1.. {
+        let file_name = match attempt {
+            1 => format!("{stem}.{extension}.md"),
+            attempt => format!("{stem} {attempt}.{extension}.md"),
+        };
+        let path = dir.join(file_name);
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let written = file
+                    .write_all(content.as_bytes())
+                    .and_then(|()| file.sync_all());
+                if let Err(error) = written {
+                    let _ = fs::remove_file(&path);
+                    return Err(error.into());
+                }
+                return Ok(path);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    unreachable!("attempts are unbounded")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lore_core::{Document, Value};
+    use tempfile::tempdir;
+
+    use super::{create_entity, create_entity_from_template, entity_file_stem};
+    use crate::registry::WorkspaceError;
+
+    #[test]
+    fn creates_entity_with_frontmatter_defaults() {
+        let temp = tempdir().expect("tempdir");
+
+        let path = create_entity(temp.path(), "character", "Maren Holt", None).expect("created");
+
+        assert_eq!(path, "Characters/Maren Holt.character.md");
+        let content = fs::read_to_string(temp.path().join(&path)).expect("read");
+        let document = Document::parse(&content).expect("parsed");
+        assert_eq!(
+            document.get("type").and_then(|value| value.as_str()),
+            Some("character")
+        );
+        assert_eq!(
+            document.get("name").and_then(|value| value.as_str()),
+            Some("Maren Holt")
+        );
+        assert_eq!(
+            document.get("status").and_then(|value| value.as_str()),
+            Some("active")
+        );
+        assert_eq!(document.body(), "# Maren Holt\n");
+    }
+
+    #[test]
+    fn uses_workspace_template_and_keeps_its_values() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore/templates")).expect("create templates");
+        fs::write(
+            temp.path().join(".lore/templates/character.md"),
+            "---\nstatus: unknown\nrole: \"\"\n---\n# {{name}}\n\n## Backstory\n",
+        )
+        .expect("write template");
+
+        let path =
+            create_entity(temp.path(), "character", "Oskar Vell", Some("Cast")).expect("created");
+
+        assert_eq!(path, "Cast/Oskar Vell.character.md");
+        let content = fs::read_to_string(temp.path().join(&path)).expect("read");
+        assert_eq!(
+            content,
+            "---\nstatus: unknown\nrole: \"\"\ntype: character\nname: Oskar Vell\n---\n# Oskar Vell\n\n## Backstory\n"
+        );
+    }
+
+    #[test]
+    fn escapes_names_rendered_into_template_frontmatter() {
+        let temp = tempdir().expect("tempdir");
+        let template = "---\ntitle: \"{{name}}\"\nalias: {{name}}\n---\n# {{name}}\n";
+
+        let path = create_entity_from_template(
+            temp.path(),
+            "character",
+            "Ana \"La Cuervo\" Ruiz",
+            None,
+            template,
+        )
+        .expect("created");
+
+        let content = fs::read_to_string(temp.path().join(&path)).expect("read");
+        let document = Document::parse(&content).expect("parse");
+        let name = Value::from("Ana \"La Cuervo\" Ruiz");
+        assert_eq!(document.get("title"), Some(&name));
+        assert_eq!(document.get("alias"), Some(&name));
+        assert_eq!(document.get("name"), Some(&name));
+        assert_eq!(document.body(), "# Ana \"La Cuervo\" Ruiz\n");
+    }
+
+    #[test]
+    fn avoids_collisions_and_unsafe_names() {
+        let temp = tempdir().expect("tempdir");
+
+        let first = create_entity_from_template(temp.path(), "location", "Salt/Reach", None, "")
+            .expect("created");
+        let second = create_entity_from_template(temp.path(), "location", "Salt/Reach", None, "")
+            .expect("created");
+
+        assert_eq!(first, "Places/Salt-Reach.location.md");
+        assert_eq!(second, "Places/Salt-Reach 2.location.md");
+        assert_eq!(
+            entity_file_stem("  ..The   Brine\tArchive. "),
+            "The Brine Archive"
+        );
+        assert_eq!(entity_file_stem("..."), "Untitled");
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_escaping_folders() {
+        let temp = tempdir().expect("tempdir");
+
+        assert!(matches!(
+            create_entity(temp.path(), "dragon", "Vyrm", None),
+            Err(WorkspaceError::UnknownEntityType(_))
+        ));
+        assert!(matches!(
+            create_entity(temp.path(), "character", "  ", None),
+            Err(WorkspaceError::EmptyEntityName)
+        ));
+        assert!(matches!(
+            create_entity(temp.path(), "character", "Maren", Some("../outside")),
+            Err(WorkspaceError::PathOutsideWorkspace(_))
+        ));
+    }
+
+    #[test]
+    fn reads_templates_only_for_known_types() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore/templates/character.md"))
+            .expect("create folder in place of a template");
+        fs::write(temp.path().join("x.md"), "---\nsecret: yes\n---\n").expect("write x");
+
+        assert!(matches!(
+            create_entity(temp.path(), "../../x", "Vyrm", None),
+            Err(WorkspaceError::UnknownEntityType(_))
+        ));
+        assert!(matches!(
+            create_entity(temp.path(), ".lore/templates", "Vyrm", None),
+            Err(WorkspaceError::UnknownEntityType(_))
+        ));
+
+        let path = create_entity(temp.path(), "character", "Maren", None).expect("created");
+        let content = fs::read_to_string(temp.path().join(path)).expect("read");
+        assert!(content.ends_with("---\n# Maren\n"), "{content}");
+    }
+}
@@ -0,0 +1,458 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{documents::TEMP_FILE_SUFFIX, registry::INTERNAL_DIR, scaffold::IGNORE_FILE};
+
+/// Decides which workspace paths are excluded from indexing and watching.
+///
+/// Rules come from the `.loreignore` at the root and from nested
+/// `.loreignore` files, using `.gitignore` semantics: later rules win, `!`
+/// re-includes, a trailing `/` matches folders only, and a pattern containing
+/// `/` is anchored to the folder of the file that declares it. The internal
+/// `.lore/` folder and in-progress save files are always excluded.
+#[derive(Debug, Clone)]
+pub struct WorkspaceFilter {
+    root: PathBuf,
+    rule_sets: Vec<RuleSet>,
+}
+
+#[derive(Debug, Clone)]
+struct RuleSet {
+    /// Folder holding the `.loreignore`, relative to the workspace root.
+    base: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    tokens: Vec<Token>,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(char),
+    /// `?`
+    AnyChar,
+    /// `*`, anything but `/`.
+    Star,
+    /// `**/`, zero or more whole folders.
+    AnyDirs,
+    /// Trailing `**`, anything including `/`.
+    AnyPath,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl WorkspaceFilter {
+    /// Reads every `.loreignore` in the workspace, skipping folders that are
+    /// themselves ignored.
+    pub fn load(root: impl AsRef<Path>) -> io::Result<Self> {
+        let mut filter = Self {
+            root: root.as_ref().to_path_buf(),
+            rule_sets: Vec::new(),
+        };
+        let root = filter.root.clone();
+        filter.load_dir(&root, Vec::new())?;
+        Ok(filter)
+    }
+
+    /// Builds a filter from the contents of a root `.loreignore` only.
+    pub fn from_rules(root: impl AsRef<Path>, contents: &str) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            rule_sets: vec![RuleSet {
+                base: Vec::new(),
+                rules: parse_rules(contents),
+            }],
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether `path` (absolute, or relative to the workspace root) is
+    /// excluded. Folder-only rules are resolved by checking the filesystem;
+    /// paths outside the workspace are always reported as ignored.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        self.is_ignored_entry(path, absolute.is_dir())
+    }
+
+    /// Like [`is_ignored`](Self::is_ignored), for callers that already know
+    /// whether the path is a folder, e.g. for paths that were just removed.
+    pub fn is_ignored_entry(&self, path: &Path, is_dir: bool) -> bool {
+        let Some(components) = self.relative_components(path) else {
+            return true;
+        };
+
+        if components.is_empty() {
+            return false;
+        }
+
+        if components[0] == INTERNAL_DIR {
+            return true;
+        }
+
+        if components
+            .last()
+            .is_some_and(|name| name.ends_with(TEMP_FILE_SUFFIX))
+        {
+            return true;
+        }
+
+        (1..=components.len()).any(|depth| {
+            let entry_is_dir = depth < components.len() || is_dir;
+            self.matches(&components[..depth], entry_is_dir)
+        })
+    }
+
+    fn relative_components(&self, path: &Path) -> Option<Vec<String>> {
+        let relative = if path.is_absolute() {
+            path.strip_prefix(&self.root).ok()?
+        } else {
+            path
+        };
+
+        let mut components = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => components.push(part.to_string_lossy().into_owned()),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+
+        Some(components)
+    }
+
+    /// Applies the rules to a single entry, ignoring its ancestors.
+    fn matches(&self, components: &[String], is_dir: bool) -> bool {
+        let mut ignored = false;
+
+        for rule_set in &self.rule_sets {
+            let inside_base =
+                components.len() > rule_set.base.len() && components.starts_with(&rule_set.base);
+            if !inside_base {
+                continue;
+            }
+
+            let relative = &components[rule_set.base.len()..];
+            for rule in &rule_set.rules {
+                if rule.matches(relative, is_dir) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+
+        ignored
+    }
+
+    fn load_dir(&mut self, dir: &Path, base: Vec<String>) -> io::Result<()> {
+        match fs::read_to_string(dir.join(IGNORE_FILE)) {
+            Ok(contents) => self.rule_sets.push(RuleSet {
+                base: base.clone(),
+                rules: parse_rules(&contents),
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        let mut children = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+
+            let mut child = base.clone();
+            child.push(entry.file_name().to_string_lossy().into_owned());
+            children.push((entry.path(), child));
+        }
+        children.sort();
+
+        for (path, child) in children {
+            if !self.is_ignored_entry(&self.root.join(child.join("/")), true) {
+                self.load_dir(&path, child)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Rule {
+    fn matches(&self, components: &[String], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            let text: Vec<char> = components.join("/").chars().collect();
+            glob_match(&self.tokens, &text)
+        } else {
+            let Some(name) = components.last() else {
+                return false;
+            };
+            let text: Vec<char> = name.chars().collect();
+            glob_match(&self.tokens, &text)
+        }
+    }
+}
+
+fn parse_rules(contents: &str) -> Vec<Rule> {
+    contents.lines().filter_map(parse_rule).collect()
+}
+
+fn parse_rule(line: &str) -> Option<Rule> {
+    let line = trim_trailing_spaces(line);
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+    if pattern.is_empty() {
+        return None;
+    }
+
+    Some(Rule {
+        tokens: tokenize(pattern),
+        negated,
+        dir_only,
+        anchored,
+    })
+}
+
+/// Trailing spaces are dropped unless escaped with a backslash.
+fn trim_trailing_spaces(line: &str) -> &str {
+    let trimmed = line.trim_end_matches([' ', '\t', '\r']);
+    if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+fn tokenize(pattern: &str) -> Vec<Token> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let ch = chars[index];
+        match ch {
+            '*' if chars.get(index + 1) == Some(&'*') => {
+                let at_segment_start = index == 0 || chars[index - 1] == '/';
+                let next = chars.get(index + 2);
+                if at_segment_start && next == Some(&'/') {
+                    tokens.push(Token::AnyDirs);
+                    index += 3;
+                } else if at_segment_start && next.is_none() {
+                    tokens.push(Token::AnyPath);
+                    index += 2;
+                } else {
+                    tokens.push(Token::Star);
+                    index += 2;
+                }
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                index += 1;
+            }
+            '?' => {
+                tokens.push(Token::AnyChar);
+                index += 1;
+            }
+            '[' => match parse_class(&chars[index..]) {
+                Some((token, consumed)) => {
+                    tokens.push(token);
+                    index += consumed;
+                }
+                None => {
+                    tokens.push(Token::Literal('['));
+                    index += 1;
+                }
+            },
+            '\\' if index + 1 < chars.len() => {
+                tokens.push(Token::Literal(chars[index + 1]));
+                index += 2;
+            }
+            _ => {
+                tokens.push(Token::Literal(ch));
+                index += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Parses `[...]` at the start of `chars`, returning the token and the number
+/// of characters consumed, or `None` when the bracket is never closed.
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let mut index = 1;
+    let negated = matches!(chars.get(index), Some('!' | '^'));
+    if negated {
+        index += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    while index < chars.len() {
+        let ch = chars[index];
+        if ch == ']' && !first {
+            return Some((Token::Class { negated, ranges }, index + 1));
+        }
+
+        if chars.get(index + 1) == Some(&'-') && chars.get(index + 2).is_some_and(|&end| end != ']')
+        {
+            ranges.push((ch, chars[index + 2]));
+            index += 3;
+        } else {
+            ranges.push((ch, ch));
+            index += 1;
+        }
+        first = false;
+    }
+
+    None
+}
+
+fn glob_match(tokens: &[Token], text: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return text.is_empty();
+    };
+
+    match token {
+        Token::Literal(expected) => text.first() == Some(expected) && glob_match(rest, &text[1..]),
+        Token::AnyChar => text.first().is_some_and(|&ch| ch != '/') && glob_match(rest, &text[1..]),
+        Token::Class { negated, ranges } => {
+            text.first().is_some_and(|&ch| {
+                let in_class = ranges.iter().any(|&(start, end)| start <= ch && ch <= end);
+                ch != '/' && in_class != *negated
+            }) && glob_match(rest, &text[1..])
+        }
+        Token::Star => (0..=text.len())
+            .take_while(|&count| count == 0 || text[count - 1] != '/')
+            .any(|count| glob_match(rest, &text[count..])),
+        Token::AnyDirs => (0..=text.len())
+            .filter(|&count| count == 0 || text[count - 1] == '/')
+            .any(|count| glob_match(rest, &text[count..])),
+        Token::AnyPath => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::WorkspaceFilter;
+
+    fn filter(rules: &str) -> WorkspaceFilter {
+        WorkspaceFilter::from_rules("/lore", rules)
+    }
+
+    #[test]
+    fn always_ignores_internal_dir() {
+        let filter = filter("");
+
+        assert!(filter.is_ignored_entry(Path::new(".lore"), true));
+        assert!(filter.is_ignored_entry(Path::new("/lore/.lore/settings.toml"), false));
+        assert!(!filter.is_ignored_entry(Path::new("Characters/.lore-notes.md"), false));
+        assert!(filter.is_ignored_entry(Path::new("/elsewhere/file.md"), false));
+    }
+
+    #[test]
+    fn matches_basenames_at_any_depth() {
+        let filter = filter("# comment\n*.tmp\nThumbs.db\n");
+
+        assert!(filter.is_ignored_entry(Path::new("draft.tmp"), false));
+        assert!(filter.is_ignored_entry(Path::new("Characters/old/hero.tmp"), false));
+        assert!(filter.is_ignored_entry(Path::new("Places/Thumbs.db"), false));
+        assert!(!filter.is_ignored_entry(Path::new("Places/Capital.location.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("# comment"), false));
+    }
+
+    #[test]
+    fn supports_negation_with_last_rule_winning() {
+        let filter = filter("*.md\n!Keep.md\n");
+
+        assert!(filter.is_ignored_entry(Path::new("Notes/Drop.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("Notes/Keep.md"), false));
+    }
+
+    #[test]
+    fn directory_patterns_only_match_folders_and_their_contents() {
+        let filter = filter("build/\n");
+
+        assert!(filter.is_ignored_entry(Path::new("build"), true));
+        assert!(filter.is_ignored_entry(Path::new("tools/build/out.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("build"), false));
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_their_folder() {
+        let filter = filter("/Drafts\nArchive/*.md\ndocs/**/scratch\n");
+
+        assert!(filter.is_ignored_entry(Path::new("Drafts/one.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("Story/Drafts/one.md"), false));
+        assert!(filter.is_ignored_entry(Path::new("Archive/old.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("Archive/2020/old.md"), false));
+        assert!(filter.is_ignored_entry(Path::new("docs/scratch"), false));
+        assert!(filter.is_ignored_entry(Path::new("docs/a/b/scratch"), false));
+    }
+
+    #[test]
+    fn supports_character_classes_and_wildcards() {
+        let filter = filter("chapter-[0-9]?.md\n**/cache/**\n");
+
+        assert!(filter.is_ignored_entry(Path::new("chapter-12.md"), false));
+        assert!(!filter.is_ignored_entry(Path::new("chapter-x2.md"), false));
+        assert!(filter.is_ignored_entry(Path::new("deep/cache/file.bin"), false));
+    }
+
+    #[test]
+    fn loads_nested_ignore_files_relative_to_their_folder() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters/minor")).expect("create folders");
+        fs::create_dir_all(root.join("Skipped")).expect("create folders");
+        fs::write(root.join(".loreignore"), "Skipped/\n*.bak\n").expect("root ignore");
+        fs::write(root.join("Characters/.loreignore"), "/minor\n!keep.bak\n")
+            .expect("nested ignore");
+        fs::write(root.join("Skipped/.loreignore"), "!*\n").expect("ignored folder rules");
+
+        let filter = WorkspaceFilter::load(root).expect("filter loaded");
+
+        assert!(filter.is_ignored(&root.join("Characters/minor")));
+        assert!(!filter.is_ignored(&root.join("minor")));
+        assert!(filter.is_ignored(Path::new("Characters/hero.bak")));
+        assert!(!filter.is_ignored(Path::new("Characters/keep.bak")));
+        assert!(filter.is_ignored(Path::new("keep.bak")));
+        assert!(filter.is_ignored(&root.join("Skipped/anything.md")));
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, path::Path};
+
+use lore_core::{Document, DocumentError, Value};
+
+use crate::{
+    cache::MetadataCache,
+    links::{LinkReference, LinkTargets},
+    registry::WorkspaceError,
+    scan::{classify_file, entity_name, scan_workspace},
+    schema::{EntityTypes, TYPE_KEY},
+};
+
+/// A Markdown file of the workspace, parsed.
+#[derive(Debug)]
+pub(crate) struct IndexedDocument {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub document: Result<Document, DocumentError>,
+    /// Links read from the metadata cache, not yet resolved. When set, the
+    /// document holds its frontmatter only.
+    pub links: Option<Vec<LinkReference>>,
+}
+
+impl IndexedDocument {
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    pub fn parsed(&self) -> Option<&Document> {
+        self.document.as_ref().ok()
+    }
+}
+
+/// Every Markdown file of a workspace with its entity types and link
+/// targets, read in one pass.
+#[derive(Debug)]
+pub(crate) struct WorkspaceIndex {
+    pub types: EntityTypes,
+    pub targets: LinkTargets,
+    pub documents: Vec<IndexedDocument>,
+}
+
+impl WorkspaceIndex {
+    /// `root` should be canonical so paths compare equal.
+    pub fn load(root: &Path) -> Result<Self, WorkspaceError> {
+        let types = EntityTypes::load(root)?;
+        let tree = scan_workspace(root)?;
+
+        let mut targets = LinkTargets::default();
+        let mut documents = Vec::new();
+        for entry in tree.files() {
+            if !entry.kind.is_markdown() {
+                continue;
+            }
+            // Files that vanished or are not UTF-8 are skipped, not fatal.
+            let Ok(content) = fs::read_to_string(root.join(&entry.path)) else {
+                continue;
+            };
+
+            let document = Document::parse(&content);
+            let entity_type = entity_type_of(&types, &entry.name, document.as_ref().ok());
+            targets.insert(&entry.path, entity_type.as_deref(), document.as_ref().ok());
+            documents.push(IndexedDocument {
+                path: entry.path.clone(),
+                document,
+                links: None,
+            });
+        }
+
+        Ok(Self {
+            types,
+            targets,
+            documents,
+        })
+    }
+
+    /// Like [`load`](Self::load), but reads frontmatter and links from the
+    /// metadata cache, parsing only files that changed since it was written.
+    /// Document bodies are not loaded.
+    pub fn load_cached(root: &Path) -> Result<Self, WorkspaceError> {
+        let types = EntityTypes::load(root)?;
+        let cache = MetadataCache::load(root, &types)?;
+
+        let mut targets = LinkTargets::default();
+        let mut documents = Vec::with_capacity(cache.files.len());
+        for file in cache.files {
+            let document = file
+                .frontmatter
+                .map(|frontmatter| Document::new(frontmatter, ""));
+            let file_name = file.path.rsplit('/').next().unwrap_or(&file.path);
+            let entity_type = entity_type_of(&types, file_name, document.as_ref().ok());
+            targets.insert(&file.path, entity_type.as_deref(), document.as_ref().ok());
+            documents.push(IndexedDocument {
+                path: file.path,
+                document,
+                links: Some(file.links),
+            });
+        }
+
+        Ok(Self {
+            types,
+            targets,
+            documents,
+        })
+    }
+
+    pub fn document(&self, path: &str) -> Option<&IndexedDocument> {
+        self.documents.iter().find(|document| document.path == path)
+    }
+}
+
+/// Frontmatter keys used as a document's title, in order of preference.
+const TITLE_KEYS: &[&str] = &["name", "title"];
+
+/// The name a document is shown under: its frontmatter `name` or `title`,
+/// or else its file name without extensions.
+pub(crate) fn document_title<'a>(file_name: &'a str, document: Option<&'a Document>) -> &'a str {
+    document
+        .and_then(|document| {
+            TITLE_KEYS
+                .iter()
+                .find_map(|key| document.get(key).and_then(Value::as_str))
+        })
+        .unwrap_or_else(|| entity_name(file_name))
+}
+
+/// The entity type id of a document: its `type:`, or the type matching its
+/// compound extension.
+pub(crate) fn entity_type_of(
+    types: &EntityTypes,
+    file_name: &str,
+    document: Option<&Document>,
+) -> Option<String> {
+    if let Some(schema) = types.for_document(file_name, document) {
+        return Some(schema.id.clone());
+    }
+
+    document
+        .and_then(|document| document.get(TYPE_KEY))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| classify_file(file_name).1)
+}
@@ -1,7 +1,56 @@
+mod cache;
+mod documents;
+mod entities;
+mod ignore;
+mod index;
+mod links;
+mod manifest;
+mod migrations;
 mod models;
+mod open;
+mod placeholders;
+mod query;
+mod recent;
 mod registry;
+mod rename;
+mod scaffold;
+mod scan;
+mod schema;
+mod search;
+mod settings;
+mod trash;
+mod user_templates;
+mod validate;
+mod watcher;
 
+pub use cache::{MetadataRefresh, refresh_metadata_cache};
+pub use documents::{LoadedDocument, load_document, save_document};
+pub use entities::{create_entity, create_entity_from_template};
+pub use ignore::WorkspaceFilter;
+pub use links::{LinkReference, OutgoingLinks, get_backlinks, get_outgoing_links};
 pub use models::{
-    CreateWorkspaceRequest, CreateWorkspaceResult, WorkspaceTemplateSummary, WorkspaceVersion,
+    CreateWorkspaceRequest, CreateWorkspaceResult, OpenedWorkspace, WorkspaceTemplateMeta,
+    WorkspaceTemplateSummary, WorkspaceVersion,
 };
-pub use registry::{WorkspaceError, create_workspace, list_workspace_templates};
+pub use open::open_workspace;
+pub use query::{QueryResult, QueryRow, run_query};
+pub use recent::{MAX_RECENT_WORKSPACES, RecentWorkspace, RecentWorkspaces};
+pub use registry::{
+    WorkspaceError, create_workspace, create_workspace_from, list_workspace_templates,
+    list_workspace_templates_from,
+};
+pub use rename::{LinkEdit, RenameResult, rename_entity};
+pub use scan::{EntryKind, WorkspaceEntry, WorkspaceTree, scan_workspace};
+pub use schema::{EntitySchema, FieldKind, FieldSchema, entity_schema_for_file, list_entity_types};
+pub use search::{DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT, SearchFilters, SearchHit, SearchIndex};
+pub use settings::{
+    ExportFormat, ExportSettings, LinkStyle, WorkspaceSettings, load_workspace_settings,
+    save_workspace_settings,
+};
+pub use trash::{
+    DEFAULT_TRASH_RETENTION_DAYS, TrashEntry, delete_from_trash, empty_trash, list_trash,
+    move_to_trash, purge_trash, restore_from_trash,
+};
+pub use user_templates::save_workspace_as_template;
+pub use validate::{Diagnostic, DiagnosticCode, Severity, validate_document, validate_workspace};
+pub use watcher::{DEFAULT_DEBOUNCE, WorkspaceChange, WorkspaceChanged, WorkspaceWatcher};
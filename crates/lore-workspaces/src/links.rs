@@ -0,0 +1,450 @@
+use std::{collections::HashMap, path::Path};
+
+use lore_core::{Document, Value, WikiLink, extract_links};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::resolve_workspace_path,
+    index::{IndexedDocument, WorkspaceIndex},
+    registry::WorkspaceError,
+    scan::{EntryKind, classify_file, entity_name, relative_path},
+    schema::{EntityTypes, FieldKind},
+};
+
+/// Frontmatter keys whose values also resolve links to a document.
+const NAME_KEY: &str = "name";
+const ALIASES_KEY: &str = "aliases";
+
+/// A wiki-link found in a document.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkReference {
+    /// The document containing the link.
+    pub source: String,
+    /// The target as written, e.g. `Capital` for `[[Capital|the capital]]`.
+    pub target: String,
+    pub heading: Option<String>,
+    pub alias: Option<String>,
+    /// The document the target resolves to.
+    pub resolved_path: Option<String>,
+    /// The frontmatter key holding the link; `None` for links in the body.
+    pub field: Option<String>,
+    /// Position in the file, counting from 1. For frontmatter links, the
+    /// line of their key.
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutgoingLinks {
+    pub resolved: Vec<LinkReference>,
+    /// Links whose target matches no document.
+    pub unresolved: Vec<LinkReference>,
+}
+
+/// Links from other documents that resolve to `path`, ordered by source and
+/// position.
+pub fn get_backlinks(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<Vec<LinkReference>, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+    let path = relative_path(&root, &path);
+    let index = WorkspaceIndex::load_cached(&root)?;
+
+    let mut backlinks: Vec<_> = index
+        .documents
+        .iter()
+        .filter(|document| document.path != path)
+        .flat_map(|document| index.links(document))
+        .filter(|link| link.resolved_path.as_deref() == Some(path.as_str()))
+        .collect();
+    backlinks.sort_by(|left, right| {
+        (&left.source, left.line, left.column).cmp(&(&right.source, right.line, right.column))
+    });
+    Ok(backlinks)
+}
+
+/// Links in `path`, split into those that resolve to a document and those
+/// that do not.
+pub fn get_outgoing_links(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<OutgoingLinks, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+    let path = relative_path(&root, &path);
+    let index = WorkspaceIndex::load_cached(&root)?;
+    let document = index
+        .document(&path)
+        .ok_or_else(|| WorkspaceError::DocumentNotFound(path.clone()))?;
+
+    let (resolved, unresolved) = index
+        .links(document)
+        .into_iter()
+        .partition(|link| link.resolved_path.is_some());
+    Ok(OutgoingLinks {
+        resolved,
+        unresolved,
+    })
+}
+
+impl WorkspaceIndex {
+    /// The links in a document's frontmatter, then in its body, resolved
+    /// against the workspace.
+    pub(crate) fn links(&self, document: &IndexedDocument) -> Vec<LinkReference> {
+        let mut links = match &document.links {
+            Some(links) => links.clone(),
+            None => document.find_links(&self.types),
+        };
+        for link in &mut links {
+            link.resolved_path = self.targets.resolve(&link.target).map(str::to_string);
+        }
+        links
+    }
+}
+
+impl IndexedDocument {
+    /// The links in the frontmatter, then in the body, without resolving
+    /// them. Documents whose frontmatter cannot be parsed have none.
+    pub(crate) fn find_links(&self, types: &EntityTypes) -> Vec<LinkReference> {
+        let Some(parsed) = self.parsed() else {
+            return Vec::new();
+        };
+        let schema = types.for_document(self.file_name(), Some(parsed));
+        let mut links = Vec::new();
+
+        for (key, value) in parsed.frontmatter() {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let is_link_field = schema
+                .and_then(|schema| schema.field(key))
+                .is_some_and(|field| field.value_kind() == FieldKind::Link);
+            let line = parsed.key_line(key).unwrap_or(1);
+
+            let mut found = Vec::new();
+            collect_frontmatter_links(value, is_link_field, &mut found);
+            links.extend(found.into_iter().map(|link| LinkReference {
+                field: Some(key.to_string()),
+                line,
+                column: 1,
+                ..self.reference(link)
+            }));
+        }
+
+        let body_line = parsed.body_line();
+        for link in extract_links(parsed.body()) {
+            if link.embed && is_image(&link.target) {
+                continue;
+            }
+            links.push(LinkReference {
+                line: body_line + link.line - 1,
+                column: link.column,
+                ..self.reference(link)
+            });
+        }
+
+        links
+    }
+
+    fn reference(&self, link: WikiLink) -> LinkReference {
+        LinkReference {
+            source: self.path.clone(),
+            resolved_path: None,
+            target: link.target,
+            heading: link.heading,
+            alias: link.alias,
+            field: None,
+            line: link.line,
+            column: link.column,
+        }
+    }
+}
+
+/// Wiki-links anywhere in a frontmatter value. Values of link fields may
+/// also be bare names.
+fn collect_frontmatter_links(value: &Value, is_link_field: bool, links: &mut Vec<WikiLink>) {
+    match value {
+        Value::String(text) => {
+            let found = extract_links(text);
+            if found.is_empty() && is_link_field {
+                if let Some(target) = link_target(text) {
+                    links.push(WikiLink {
+                        target,
+                        heading: None,
+                        alias: None,
+                        embed: false,
+                        span: 0..text.len(),
+                        line: 1,
+                        column: 1,
+                    });
+                }
+            } else {
+                links.extend(found);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                collect_frontmatter_links(item, is_link_field, links);
+            }
+        }
+        Value::Mapping(mapping) => {
+            for value in mapping.values() {
+                collect_frontmatter_links(value, false, links);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Image embeds such as `![[map.png]]` are not links between documents.
+fn is_image(target: &str) -> bool {
+    classify_file(target).0 == EntryKind::Image
+}
+
+/// How a link target matched a document; lower ranks win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchRank {
+    Path,
+    FileName,
+    Name,
+    Alias,
+}
+
+/// Resolves link targets such as `Maren Holt` or `Characters/Maren` to
+/// workspace documents. Matching ignores case.
+#[derive(Debug, Default)]
+pub(crate) struct LinkTargets {
+    names: HashMap<String, Vec<(MatchRank, String)>>,
+    entity_types: HashMap<String, String>,
+}
+
+impl LinkTargets {
+    /// Registers the document at `path` (relative, `/`-separated) under its
+    /// path, file name, frontmatter `name` and `aliases`.
+    pub fn insert(&mut self, path: &str, entity_type: Option<&str>, document: Option<&Document>) {
+        let (folder, file_name) = match path.rsplit_once('/') {
+            Some((folder, file_name)) => (Some(folder), file_name),
+            None => (None, path),
+        };
+        let name = entity_name(file_name);
+
+        if let Some(folder) = folder {
+            self.add(&format!("{folder}/{name}"), MatchRank::Path, path);
+        }
+        self.add(name, MatchRank::FileName, path);
+
+        if let Some(document) = document {
+            if let Some(name) = document.get(NAME_KEY).and_then(Value::as_str) {
+                self.add(name, MatchRank::Name, path);
+            }
+            let aliases = document
+                .get(ALIASES_KEY)
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str);
+            for alias in aliases {
+                self.add(alias, MatchRank::Alias, path);
+            }
+        }
+
+        if let Some(entity_type) = entity_type {
+            self.entity_types
+                .insert(path.to_string(), entity_type.to_string());
+        }
+    }
+
+    /// The best match for `target`; ties go to the first path inserted.
+    pub fn resolve(&self, target: &str) -> Option<&str> {
+        self.names
+            .get(&normalize(target))?
+            .iter()
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, path)| path.as_str())
+    }
+
+    pub fn entity_type(&self, path: &str) -> Option<&str> {
+        self.entity_types.get(path).map(String::as_str)
+    }
+
+    fn add(&mut self, name: &str, rank: MatchRank, path: &str) {
+        let key = normalize(name);
+        if key.is_empty() {
+            return;
+        }
+        self.names
+            .entry(key)
+            .or_default()
+            .push((rank, path.to_string()));
+    }
+}
+
+/// The target of a link written in frontmatter, either as a wiki-link like
+/// `"[[Saltreach|the port]]"` or as a bare name.
+pub(crate) fn link_target(value: &str) -> Option<String> {
+    if let Some(link) = WikiLink::parse(value) {
+        return Some(link.target);
+    }
+
+    let target = value.split(['|', '#']).next().unwrap_or_default().trim();
+    let is_bare = !target.is_empty() && !target.contains(['[', ']']);
+    is_bare.then(|| target.to_string())
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lore_core::Document;
+    use tempfile::tempdir;
+
+    use super::{LinkTargets, get_backlinks, get_outgoing_links, link_target};
+
+    #[test]
+    fn extracts_targets_from_link_values() {
+        assert_eq!(link_target("[[Saltreach]]").as_deref(), Some("Saltreach"));
+        assert_eq!(
+            link_target(" [[Saltreach|the port]] ").as_deref(),
+            Some("Saltreach")
+        );
+        assert_eq!(
+            link_target("[[Saltreach#Docks]]").as_deref(),
+            Some("Saltreach")
+        );
+        assert_eq!(link_target("Saltreach").as_deref(), Some("Saltreach"));
+        assert_eq!(link_target("[[ ]]").as_deref(), None);
+    }
+
+    #[test]
+    fn resolves_by_file_name_name_and_alias() {
+        let maren = Document::parse("---\nname: Maren Holt\naliases: [the Tide-Reader]\n---\n")
+            .expect("parsed");
+        let mut targets = LinkTargets::default();
+        targets.insert(
+            "Characters/Maren.character.md",
+            Some("character"),
+            Some(&maren),
+        );
+        targets.insert("Maren Holt.md", None, None);
+
+        assert_eq!(
+            targets.resolve("maren"),
+            Some("Characters/Maren.character.md")
+        );
+        assert_eq!(
+            targets.resolve("The Tide-Reader"),
+            Some("Characters/Maren.character.md")
+        );
+        assert_eq!(
+            targets.resolve("Characters/Maren"),
+            Some("Characters/Maren.character.md")
+        );
+        assert_eq!(targets.resolve("Maren Holt"), Some("Maren Holt.md"));
+        assert_eq!(targets.resolve("Saltreach"), None);
+        assert_eq!(
+            targets.entity_type("Characters/Maren.character.md"),
+            Some("character")
+        );
+    }
+
+    #[test]
+    fn ties_resolve_to_the_first_path_inserted() {
+        let mut targets = LinkTargets::default();
+        targets.insert("Places/Saltreach.md", None, None);
+        targets.insert("Archive/Saltreach.md", None, None);
+
+        assert_eq!(targets.resolve("Saltreach"), Some("Places/Saltreach.md"));
+    }
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters")).expect("create characters");
+        fs::create_dir_all(root.join("Places")).expect("create places");
+        fs::write(
+            root.join("Characters/Elandra.character.md"),
+            "---\nname: Elandra Vosh\nhome: \"[[Capital]]\"\nallies: [Oskar]\n---\n# Elandra\n\nRules [[Capital|the capital]] from [[Nowhere]].\n![[map.png]]\n",
+        )
+        .expect("write character");
+        fs::write(
+            root.join("Places/Capital.location.md"),
+            "---\nname: Capital\naliases: [the Crown City]\n---\n[[Capital]] is home to [[Elandra Vosh]].\n",
+        )
+        .expect("write location");
+        fs::write(
+            root.join("Chapter 01.md"),
+            "Arrival in `[[Capital]]` and then [[the Crown City]].\n",
+        )
+        .expect("write chapter");
+        temp
+    }
+
+    #[test]
+    fn splits_outgoing_links_into_resolved_and_unresolved() {
+        let temp = workspace();
+
+        let links =
+            get_outgoing_links(temp.path(), "Characters/Elandra.character.md").expect("links");
+
+        let resolved: Vec<_> = links
+            .resolved
+            .iter()
+            .map(|link| {
+                (
+                    link.target.as_str(),
+                    link.field.as_deref(),
+                    link.line,
+                    link.resolved_path.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            resolved,
+            [
+                (
+                    "Capital",
+                    Some("home"),
+                    3,
+                    Some("Places/Capital.location.md")
+                ),
+                ("Capital", None, 8, Some("Places/Capital.location.md")),
+            ]
+        );
+        assert_eq!(links.resolved[1].alias.as_deref(), Some("the capital"));
+        let unresolved: Vec<_> = links
+            .unresolved
+            .iter()
+            .map(|link| link.target.as_str())
+            .collect();
+        assert_eq!(unresolved, ["Oskar", "Nowhere"]);
+    }
+
+    #[test]
+    fn finds_backlinks_by_file_name_name_and_alias() {
+        let temp = workspace();
+
+        let backlinks = get_backlinks(temp.path(), "Places/Capital.location.md").expect("links");
+
+        let sources: Vec<_> = backlinks
+            .iter()
+            .map(|link| (link.source.as_str(), link.target.as_str()))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("Chapter 01.md", "the Crown City"),
+                ("Characters/Elandra.character.md", "Capital"),
+                ("Characters/Elandra.character.md", "Capital"),
+            ]
+        );
+        let elandra = get_backlinks(temp.path(), "Characters/Elandra.character.md").expect("links");
+        assert_eq!(elandra.len(), 1);
+        assert_eq!(elandra[0].column, 24);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::registry::WorkspaceError;
+
+pub(crate) const MANIFEST_EXTENSION: &str = "lore";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct WorkspaceManifest {
+    /// Missing from manifests written before ids existed; backfilled on open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub workspace_version: u32,
+    pub created_with: String,
+    pub created_at: String,
+    pub template_id: String,
+    /// Keys this version does not know about, kept so rewrites don't drop them.
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl WorkspaceManifest {
+    pub fn read(path: &Path) -> Result<Self, WorkspaceError> {
+        let contents = fs::read_to_string(path)?;
+
+        toml::from_str(&contents).map_err(|error| WorkspaceError::MalformedManifest {
+            path: path.display().to_string(),
+            reason: error.message().to_string(),
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), WorkspaceError> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Locates the single `*.lore` manifest at the top level of `root`.
+pub(crate) fn find_manifest(root: &Path) -> Result<PathBuf, WorkspaceError> {
+    let mut manifests = Vec::new();
+
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let path = entry.path();
+        let is_manifest = path
+            .extension()
+            .is_some_and(|extension| extension == MANIFEST_EXTENSION);
+
+        if is_manifest && entry.file_type()?.is_file() {
+            manifests.push(path);
+        }
+    }
+
+    match manifests.len() {
+        0 => Err(WorkspaceError::MissingManifest(root.display().to_string())),
+        1 => Ok(manifests.remove(0)),
+        _ => {
+            manifests.sort();
+            Err(WorkspaceError::DuplicateManifest(
+                manifests
+                    .iter()
+                    .filter_map(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ))
+        }
+    }
+}
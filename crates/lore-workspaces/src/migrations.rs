@@ -0,0 +1,300 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+
+use crate::{
+    models::WORKSPACE_VERSION,
+    registry::{INTERNAL_DIR, WorkspaceError},
+};
+
+pub(crate) const BACKUPS_DIR: &str = "backups";
+const VERSION_KEY: &str = "workspace_version";
+
+/// A single upgrade step from `from` to `from + 1`.
+///
+/// Steps must be idempotent: the manifest version is bumped only after a step
+/// succeeds, so a step interrupted halfway runs again on the next open.
+pub(crate) struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut MigrationContext<'_>) -> Result<(), WorkspaceError>,
+}
+
+// Only read by migration steps, and none have shipped yet.
+#[allow(dead_code)]
+pub(crate) struct MigrationContext<'a> {
+    pub root: &'a Path,
+    pub manifest: &'a mut toml::Table,
+}
+
+/// Ordered upgrade steps. No format changes have shipped since version 1.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Brings the workspace at `root` up to [`WORKSPACE_VERSION`], returning the
+/// version it was migrated from, if any migration ran.
+pub(crate) fn migrate_workspace(
+    root: &Path,
+    manifest_path: &Path,
+) -> Result<Option<u32>, WorkspaceError> {
+    run_migrations(root, manifest_path, MIGRATIONS, WORKSPACE_VERSION)
+}
+
+fn run_migrations(
+    root: &Path,
+    manifest_path: &Path,
+    migrations: &[Migration],
+    target: u32,
+) -> Result<Option<u32>, WorkspaceError> {
+    let mut manifest = read_manifest_table(manifest_path)?;
+    let found = manifest_version(&manifest, manifest_path)?;
+
+    if found > target {
+        return Err(WorkspaceError::UnsupportedVersion {
+            found,
+            supported: target,
+        });
+    }
+
+    if found == target {
+        return Ok(None);
+    }
+
+    backup_workspace(root, manifest_path, found)?;
+
+    for version in found..target {
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.from == version)
+            .ok_or(WorkspaceError::MissingMigration(version))?;
+
+        (migration.apply)(&mut MigrationContext {
+            root,
+            manifest: &mut manifest,
+        })
+        .map_err(|error| WorkspaceError::MigrationFailed {
+            from: version,
+            description: migration.description.to_string(),
+            reason: error.to_string(),
+        })?;
+
+        manifest.insert(
+            VERSION_KEY.to_string(),
+            toml::Value::Integer(i64::from(version + 1)),
+        );
+        fs::write(manifest_path, toml::to_string_pretty(&manifest)?)?;
+    }
+
+    Ok(Some(found))
+}
+
+fn read_manifest_table(manifest_path: &Path) -> Result<toml::Table, WorkspaceError> {
+    let contents = fs::read_to_string(manifest_path)?;
+
+    contents
+        .parse::<toml::Table>()
+        .map_err(|error| WorkspaceError::MalformedManifest {
+            path: manifest_path.display().to_string(),
+            reason: error.message().to_string(),
+        })
+}
+
+fn manifest_version(manifest: &toml::Table, manifest_path: &Path) -> Result<u32, WorkspaceError> {
+    manifest
+        .get(VERSION_KEY)
+        .and_then(toml::Value::as_integer)
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| WorkspaceError::MalformedManifest {
+            path: manifest_path.display().to_string(),
+            reason: format!("missing or invalid `{VERSION_KEY}`"),
+        })
+}
+
+/// Copies the manifest and `.lore/` into `.lore/backups/v<version>-<timestamp>/`
+/// before anything is rewritten.
+fn backup_workspace(
+    root: &Path,
+    manifest_path: &Path,
+    version: u32,
+) -> Result<PathBuf, WorkspaceError> {
+    let internal_dir = root.join(INTERNAL_DIR);
+    let backups_dir = internal_dir.join(BACKUPS_DIR);
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+
+    let mut backup_dir = backups_dir.join(format!("v{version}-{stamp}"));
+    let mut attempt = 1;
+    while backup_dir.exists() {
+        attempt += 1;
+        backup_dir = backups_dir.join(format!("v{version}-{stamp}-{attempt}"));
+    }
+
+    fs::create_dir_all(&backup_dir)?;
+
+    if let Some(file_name) = manifest_path.file_name() {
+        fs::copy(manifest_path, backup_dir.join(file_name))?;
+    }
+
+    if internal_dir.is_dir() {
+        copy_dir(&internal_dir, &backup_dir.join(INTERNAL_DIR), &|path| {
+            path == backups_dir
+        })?;
+    }
+
+    Ok(backup_dir)
+}
+
+/// Copies `from` into `to`, leaving out what `skip` matches. Symlinks are
+/// skipped too: copying one would copy whatever it points to.
+pub(crate) fn copy_dir(from: &Path, to: &Path, skip: &dyn Fn(&Path) -> bool) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if skip(&path) {
+            continue;
+        }
+
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            copy_dir(&path, &target, skip)?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use tempfile::tempdir;
+
+    use super::{Migration, MigrationContext, run_migrations};
+    use crate::registry::WorkspaceError;
+
+    fn add_tagline(context: &mut MigrationContext<'_>) -> Result<(), WorkspaceError> {
+        context
+            .manifest
+            .entry("tagline")
+            .or_insert_with(|| toml::Value::String(String::new()));
+        Ok(())
+    }
+
+    fn add_drafts_folder(context: &mut MigrationContext<'_>) -> Result<(), WorkspaceError> {
+        fs::create_dir_all(context.root.join("Drafts"))?;
+        Ok(())
+    }
+
+    const STEPS: &[Migration] = &[
+        Migration {
+            from: 2,
+            description: "Create Drafts folder",
+            apply: add_drafts_folder,
+        },
+        Migration {
+            from: 1,
+            description: "Add manifest tagline",
+            apply: add_tagline,
+        },
+    ];
+
+    fn seed(root: &Path, version: u32) -> PathBuf {
+        fs::create_dir_all(root.join(".lore")).expect("create .lore");
+        fs::write(root.join(".lore/settings.toml"), "theme = \"dark\"\n").expect("seed settings");
+        let manifest_path = root.join("atlas.lore");
+        fs::write(
+            &manifest_path,
+            format!("name = \"Atlas\"\nworkspace_version = {version}\n"),
+        )
+        .expect("seed manifest");
+        manifest_path
+    }
+
+    #[test]
+    fn runs_steps_in_order_and_bumps_version() {
+        let temp = tempdir().expect("tempdir");
+        let manifest_path = seed(temp.path(), 1);
+
+        let migrated_from =
+            run_migrations(temp.path(), &manifest_path, STEPS, 3).expect("migrated");
+
+        let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+        assert_eq!(migrated_from, Some(1));
+        assert!(manifest.contains("workspace_version = 3"));
+        assert!(manifest.contains("tagline = \"\""));
+        assert!(temp.path().join("Drafts").is_dir());
+    }
+
+    #[test]
+    fn backs_up_manifest_and_internal_dir_before_migrating() {
+        let temp = tempdir().expect("tempdir");
+        let manifest_path = seed(temp.path(), 1);
+
+        run_migrations(temp.path(), &manifest_path, STEPS, 3).expect("migrated");
+
+        let backups: Vec<_> = fs::read_dir(temp.path().join(".lore/backups"))
+            .expect("backups dir")
+            .map(|entry| entry.expect("entry").path())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let backup = &backups[0];
+        assert!(backup.join(".lore/settings.toml").is_file());
+        assert!(!backup.join(".lore/backups").exists());
+        let manifest = fs::read_to_string(backup.join("atlas.lore")).expect("backup manifest");
+        assert!(manifest.contains("workspace_version = 1"));
+    }
+
+    #[test]
+    fn leaves_current_workspace_untouched() {
+        let temp = tempdir().expect("tempdir");
+        let manifest_path = seed(temp.path(), 3);
+
+        let migrated_from = run_migrations(temp.path(), &manifest_path, STEPS, 3).expect("no-op");
+
+        assert_eq!(migrated_from, None);
+        assert!(!temp.path().join(".lore/backups").exists());
+    }
+
+    #[test]
+    fn refuses_workspace_from_newer_app() {
+        let temp = tempdir().expect("tempdir");
+        let manifest_path = seed(temp.path(), 4);
+
+        let error = run_migrations(temp.path(), &manifest_path, STEPS, 3).expect_err("must fail");
+
+        assert!(matches!(
+            error,
+            WorkspaceError::UnsupportedVersion {
+                found: 4,
+                supported: 3
+            }
+        ));
+        let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+        assert!(manifest.contains("workspace_version = 4"));
+    }
+
+    #[test]
+    fn reports_gap_in_migration_chain() {
+        let temp = tempdir().expect("tempdir");
+        let manifest_path = seed(temp.path(), 1);
+
+        let error =
+            run_migrations(temp.path(), &manifest_path, &STEPS[1..], 3).expect_err("must fail");
+
+        assert!(matches!(error, WorkspaceError::MissingMigration(2)));
+        let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+        assert!(manifest.contains("workspace_version = 2"));
+    }
+}
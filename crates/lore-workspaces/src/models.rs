@@ -10,17 +10,35 @@ pub struct CreateWorkspaceRequest {
     pub parent_path: String,
     pub template_id: String,
     pub app_version: String,
+    /// Fills `{{author}}` in template files.
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWorkspaceResult {
+    pub id: String,
+    pub name: String,
+    pub root_path: String,
+    pub manifest_path: String,
+    pub created_at: String,
+    pub template_id: String,
+    pub workspace_version: WorkspaceVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedWorkspace {
+    pub id: String,
     pub name: String,
     pub root_path: String,
     pub manifest_path: String,
     pub created_at: String,
+    pub created_with: String,
     pub template_id: String,
     pub workspace_version: WorkspaceVersion,
+    pub migrated_from: Option<WorkspaceVersion>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +52,20 @@ pub struct WorkspaceTemplateSummary {
     pub sort_order: u16,
 }
 
+/// Describes a template saved from an existing workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTemplateMeta {
+    /// Derived from `display_name` when omitted.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub sort_order: Option<u16>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceVersion {
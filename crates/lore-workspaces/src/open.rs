@@ -0,0 +1,266 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    manifest::{MANIFEST_EXTENSION, WorkspaceManifest, find_manifest},
+    migrations::migrate_workspace,
+    models::{OpenedWorkspace, WorkspaceVersion},
+    registry::{INTERNAL_DIR, SETTINGS_FILE, STATE_FILE, WorkspaceError},
+};
+
+/// Opens an existing workspace from its root folder or its `*.lore` manifest,
+/// migrating it to the current format first when it was written by an older app.
+pub fn open_workspace(path: impl AsRef<Path>) -> Result<OpenedWorkspace, WorkspaceError> {
+    let (root_path, manifest_path) = resolve_workspace_paths(path.as_ref())?;
+    let migrated_from = migrate_workspace(&root_path, &manifest_path)?;
+
+    ensure_internal_dir(&root_path)?;
+
+    let mut manifest = WorkspaceManifest::read(&manifest_path)?;
+    let id = match manifest.id {
+        Some(id) => id,
+        None => {
+            let id = Uuid::new_v4();
+            manifest.id = Some(id);
+            manifest.write(&manifest_path)?;
+            id
+        }
+    };
+
+    Ok(OpenedWorkspace {
+        id: id.to_string(),
+        name: manifest.name,
+        root_path: root_path.display().to_string(),
+        manifest_path: manifest_path.display().to_string(),
+        created_at: manifest.created_at,
+        created_with: manifest.created_with,
+        template_id: manifest.template_id,
+        workspace_version: WorkspaceVersion {
+            major: manifest.workspace_version,
+        },
+        migrated_from: migrated_from.map(|major| WorkspaceVersion { major }),
+    })
+}
+
+fn resolve_workspace_paths(path: &Path) -> Result<(PathBuf, PathBuf), WorkspaceError> {
+    if path.as_os_str().is_empty() {
+        return Err(WorkspaceError::EmptyPath);
+    }
+
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(WorkspaceError::WorkspaceNotFound(
+                path.display().to_string(),
+            ));
+        }
+        Err(error) => return Err(WorkspaceError::Io(error)),
+    };
+
+    if metadata.is_dir() {
+        return Ok((path.to_path_buf(), find_manifest(path)?));
+    }
+
+    let is_manifest = path
+        .extension()
+        .is_some_and(|extension| extension == MANIFEST_EXTENSION);
+    match path.parent() {
+        Some(root) if is_manifest => Ok((root.to_path_buf(), path.to_path_buf())),
+        _ => Err(WorkspaceError::MissingManifest(path.display().to_string())),
+    }
+}
+
+/// Verifies `.lore/` is a folder and restores any bookkeeping files that went
+/// missing, e.g. when a workspace was copied without its hidden files.
+fn ensure_internal_dir(root_path: &Path) -> Result<(), WorkspaceError> {
+    let internal_dir = root_path.join(INTERNAL_DIR);
+
+    match fs::metadata(&internal_dir) {
+        Ok(metadata) if !metadata.is_dir() => {
+            return Err(WorkspaceError::CorruptInternalDir(
+                internal_dir.display().to_string(),
+            ));
+        }
+        Ok(_) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            fs::create_dir_all(&internal_dir)?;
+        }
+        Err(error) => return Err(WorkspaceError::Io(error)),
+    }
+
+    let settings_path = internal_dir.join(SETTINGS_FILE);
+    if !settings_path.is_file() {
+        fs::write(settings_path, "")?;
+    }
+
+    let state_path = internal_dir.join(STATE_FILE);
+    if !state_path.is_file() {
+        fs::write(state_path, "{}\n")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::open_workspace;
+    use crate::{CreateWorkspaceRequest, create_workspace};
+
+    fn create(parent: &Path, name: &str) -> String {
+        create_workspace(CreateWorkspaceRequest {
+            name: name.to_string(),
+            parent_path: parent.display().to_string(),
+            template_id: "blank".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created")
+        .root_path
+    }
+
+    #[test]
+    fn opens_created_workspace() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "The Ashen Coast");
+
+        let opened = open_workspace(&root_path).expect("workspace opened");
+
+        assert_eq!(opened.name, "The Ashen Coast");
+        assert_eq!(opened.template_id, "blank");
+        assert_eq!(opened.workspace_version.major, 1);
+        assert_eq!(opened.root_path, root_path);
+        assert_eq!(opened.created_with, "0.1.0");
+    }
+
+    #[test]
+    fn keeps_id_assigned_at_creation() {
+        let temp = tempdir().expect("tempdir");
+        let created = create_workspace(CreateWorkspaceRequest {
+            name: "Stable".to_string(),
+            parent_path: temp.path().display().to_string(),
+            template_id: "blank".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created");
+
+        let opened = open_workspace(&created.root_path).expect("workspace opened");
+
+        assert_eq!(opened.id, created.id);
+    }
+
+    #[test]
+    fn backfills_id_for_older_manifest_and_keeps_unknown_keys() {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore")).expect("create .lore");
+        let manifest_path = temp.path().join("legacy.lore");
+        fs::write(
+            &manifest_path,
+            "name = \"Legacy\"\nworkspace_version = 1\ncreated_with = \"0.0.9\"\n\
+             created_at = \"2024-05-01T00:00:00+00:00\"\ntemplate_id = \"blank\"\n\
+             cover = \"ember\"\n",
+        )
+        .expect("seed manifest");
+
+        let first = open_workspace(temp.path()).expect("workspace opened");
+        let second = open_workspace(temp.path()).expect("workspace reopened");
+
+        assert!(uuid::Uuid::parse_str(&first.id).is_ok());
+        assert_eq!(first.id, second.id);
+        let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+        assert!(manifest.contains(&format!("id = \"{}\"", first.id)));
+        assert!(manifest.contains("cover = \"ember\""));
+    }
+
+    #[test]
+    fn opens_workspace_from_manifest_path() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "Quiet Atlas");
+        let manifest_path = Path::new(&root_path).join("quiet-atlas.lore");
+
+        let opened = open_workspace(&manifest_path).expect("workspace opened");
+
+        assert_eq!(opened.root_path, root_path);
+    }
+
+    #[test]
+    fn restores_missing_internal_files() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "Copied");
+        let internal_dir = Path::new(&root_path).join(".lore");
+        fs::remove_dir_all(&internal_dir).expect("remove .lore");
+
+        open_workspace(&root_path).expect("workspace opened");
+
+        assert!(internal_dir.join("settings.toml").is_file());
+        assert!(internal_dir.join("state.json").is_file());
+    }
+
+    #[test]
+    fn rejects_folder_without_manifest() {
+        let temp = tempdir().expect("tempdir");
+
+        let error = open_workspace(temp.path()).expect_err("must fail");
+
+        assert!(error.to_string().contains("No workspace manifest"));
+    }
+
+    #[test]
+    fn rejects_duplicate_manifests() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "Twice");
+        fs::write(Path::new(&root_path).join("other.lore"), "").expect("seed manifest");
+
+        let error = open_workspace(&root_path).expect_err("must fail");
+
+        assert!(error.to_string().contains("other.lore, twice.lore"));
+    }
+
+    #[test]
+    fn rejects_malformed_manifest() {
+        let temp = tempdir().expect("tempdir");
+        fs::write(temp.path().join("broken.lore"), "name = \"Broken\"\n").expect("seed manifest");
+
+        let error = open_workspace(temp.path()).expect_err("must fail");
+
+        assert!(error.to_string().contains("malformed"));
+    }
+
+    #[test]
+    fn refuses_workspace_from_newer_app() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "Future");
+        let manifest_path = Path::new(&root_path).join("future.lore");
+        let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+        fs::write(
+            &manifest_path,
+            manifest.replace("workspace_version = 1", "workspace_version = 99"),
+        )
+        .expect("bump version");
+
+        let error = open_workspace(&root_path).expect_err("must fail");
+
+        assert!(error.to_string().contains("format version 99"));
+    }
+
+    #[test]
+    fn rejects_internal_dir_that_is_a_file() {
+        let temp = tempdir().expect("tempdir");
+        let root_path = create(temp.path(), "Shadowed");
+        let internal_dir = Path::new(&root_path).join(".lore");
+        fs::remove_dir_all(&internal_dir).expect("remove .lore");
+        fs::write(&internal_dir, "").expect("seed file");
+
+        let error = open_workspace(&root_path).expect_err("must fail");
+
+        assert!(error.to_string().contains(".lore"));
+    }
+}
@@ -0,0 +1,498 @@
+/// Values for the `{{name}}` placeholders templates may use in file contents
+/// and file names.
+///
+/// Substitution is a single literal pass: values are never re-scanned, and
+/// unknown placeholders are left exactly as written.
+#[derive(Debug, Clone)]
+pub(crate) struct TemplateVariables {
+    pub workspace_name: String,
+    pub created_at: String,
+    pub author: String,
+    pub slug: String,
+}
+
+impl TemplateVariables {
+    fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "workspace_name" => Some(&self.workspace_name),
+            "created_at" => Some(&self.created_at),
+            "author" => Some(&self.author),
+            "slug" => Some(&self.slug),
+            _ => None,
+        }
+    }
+
+    pub fn render(&self, input: &str) -> String {
+        render_placeholders(input, |key| self.get(key))
+    }
+
+    /// Like [`render`](Self::render), escaping values by the extension of
+    /// `file_name`: in Markdown frontmatter and in YAML, TOML and JSON files
+    /// they are escaped for the string they land in, so a `"` or a line
+    /// break in a workspace name cannot break the file. Other files are
+    /// rendered as is.
+    pub fn render_file(&self, file_name: &str, input: &str) -> String {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        let lookup = |key: &str| self.get(key);
+
+        match extension.as_str() {
+            "md" | "markdown" => render_markdown_placeholders(input, lookup),
+            "yaml" | "yml" => {
+                let mut yaml = YamlLines::default();
+                input
+                    .split_inclusive('\n')
+                    .map(|line| match line.trim_end() {
+                        "---" | "..." => {
+                            yaml = YamlLines::default();
+                            line.to_string()
+                        }
+                        _ => yaml.render(line, &lookup),
+                    })
+                    .collect()
+            }
+            "toml" => render_quoted(input, &lookup, Syntax::Toml),
+            "json" => render_quoted(input, &lookup, Syntax::Json),
+            _ => self.render(input),
+        }
+    }
+
+    /// Renders a single path component. Characters that would change the path
+    /// (separators, reserved names on Windows) are replaced so a value can
+    /// never move a file outside the folder it was declared in.
+    pub fn render_file_name(&self, file_name: &str) -> String {
+        let rendered = self.render(file_name);
+        if rendered == file_name {
+            return rendered;
+        }
+
+        let sanitized: String = rendered
+            .chars()
+            .map(|ch| match ch {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+                ch if ch.is_control() => '-',
+                ch => ch,
+            })
+            .collect();
+        let sanitized = sanitized.trim();
+
+        if sanitized.is_empty() || sanitized.chars().all(|ch| ch == '.') {
+            file_name.to_string()
+        } else {
+            sanitized.to_string()
+        }
+    }
+}
+
+/// Replaces each `{{key}}` in `input` with `lookup(key)`, leaving unknown
+/// placeholders as written.
+pub(crate) fn render_placeholders<'a>(
+    input: &str,
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+
+        let Some(end) = after_open.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        match lookup(after_open[..end].trim()) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Like [`render_placeholders`] for a Markdown file, except that values
+/// filled into its frontmatter are escaped so any text stays a valid YAML
+/// string: inside quotes they are escaped for that quote style, and a plain
+/// scalar holding a placeholder is rewritten as a double-quoted one.
+pub(crate) fn render_markdown_placeholders<'a>(
+    input: &str,
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> String {
+    let mut lines = input.split_inclusive('\n');
+    let Some(first) = lines.next() else {
+        return String::new();
+    };
+    if first.trim_start_matches('\u{feff}').trim_end() != "---" {
+        return render_placeholders(input, lookup);
+    }
+
+    let mut output = String::with_capacity(input.len());
+    output.push_str(first);
+    let mut yaml = YamlLines::default();
+    let mut closed = false;
+
+    for line in lines.by_ref() {
+        if matches!(line.trim_end(), "---" | "...") {
+            output.push_str(line);
+            closed = true;
+            break;
+        }
+        output.push_str(&yaml.render(line, &lookup));
+    }
+
+    if !closed {
+        // Not frontmatter after all: nothing to escape.
+        return render_placeholders(input, lookup);
+    }
+    for line in lines {
+        output.push_str(&render_placeholders(line, |key| lookup(key)));
+    }
+    output
+}
+
+/// Renders YAML a line at a time, keeping track of block scalars.
+#[derive(Default)]
+struct YamlLines {
+    /// Indentation of the key whose value is a `|` or `>` block scalar.
+    block_indent: Option<usize>,
+}
+
+impl YamlLines {
+    fn render<'a>(&mut self, line: &str, lookup: &impl Fn(&str) -> Option<&'a str>) -> String {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[content.len()..];
+
+        let indent = content.len() - content.trim_start().len();
+        if let Some(key_indent) = self.block_indent {
+            if content.trim().is_empty() || indent > key_indent {
+                // Block scalars take text as is, but a line break would end them.
+                let rendered = render_placeholders(content, |key| lookup(key));
+                return rendered.replace(['\n', '\r'], " ") + ending;
+            }
+            self.block_indent = None;
+        }
+
+        let value_start = yaml_value_start(content);
+        let value = content[value_start..].trim_start();
+        if value.starts_with(['|', '>']) {
+            self.block_indent = Some(indent);
+        }
+        if !content.contains("{{") {
+            return line.to_string();
+        }
+
+        format!(
+            "{}{}{ending}",
+            &content[..value_start],
+            render_yaml_value(&content[value_start..], lookup)
+        )
+    }
+}
+
+/// Where the value starts in a YAML line: after `key:` and any
+/// `- ` list markers.
+fn yaml_value_start(line: &str) -> usize {
+    let mut start = line.len() - line.trim_start().len();
+    while line[start..].starts_with("- ") {
+        start += 2;
+        start += line[start..].len() - line[start..].trim_start().len();
+    }
+
+    let rest = &line[start..];
+    if rest.starts_with(['"', '\'', '[', '{']) {
+        return start;
+    }
+    match rest.find(": ") {
+        Some(colon) => start + colon + 1,
+        None if rest.ends_with(':') => line.len(),
+        None => start,
+    }
+}
+
+fn render_yaml_value<'a>(value: &str, lookup: &impl Fn(&str) -> Option<&'a str>) -> String {
+    let trimmed = value.trim_start();
+    let lead = &value[..value.len() - trimmed.len()];
+
+    if !trimmed.starts_with(['"', '\'', '[', '{', '|', '>']) {
+        // A plain scalar: quote all of it, keeping any trailing comment.
+        let (scalar, comment) = match trimmed.find(" #") {
+            Some(hash) => trimmed.split_at(hash),
+            None => (trimmed, ""),
+        };
+        let rendered = render_placeholders(scalar.trim_end(), |key| lookup(key));
+        let trailing = &scalar[scalar.trim_end().len()..];
+        return format!(
+            "{lead}\"{}\"{trailing}{comment}",
+            escape_double_quoted(&rendered)
+        );
+    }
+
+    render_quoted(value, lookup, Syntax::Yaml)
+}
+
+/// The string syntax of a file that placeholders are escaped for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Yaml,
+    Toml,
+    Json,
+}
+
+/// Fills placeholders, escaping each value for the quoted string it is in.
+/// Outside any string a value becomes a double-quoted string of its own.
+fn render_quoted<'a>(
+    input: &str,
+    lookup: &impl Fn(&str) -> Option<&'a str>,
+    syntax: Syntax,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut quote: Option<char> = None;
+    let mut rest = input;
+    while let Some(ch) = rest.chars().next() {
+        if rest.starts_with("{{")
+            && let Some(end) = rest[2..].find("}}")
+            && let Some(replacement) = lookup(rest[2..2 + end].trim())
+        {
+            match quote {
+                Some('"') => output.push_str(&escape_double_quoted(replacement)),
+                Some(_) if syntax == Syntax::Toml => {
+                    output.push_str(&escape_literal_string(replacement));
+                }
+                Some(_) => output.push_str(&escape_single_quoted(replacement)),
+                None => {
+                    output.push('"');
+                    output.push_str(&escape_double_quoted(replacement));
+                    output.push('"');
+                }
+            }
+            rest = &rest[2 + end + 2..];
+            continue;
+        }
+
+        let mut len = ch.len_utf8();
+        match (quote, ch) {
+            (Some('"'), '\\') => {
+                len += rest[1..].chars().next().map_or(0, char::len_utf8);
+            }
+            (Some('\''), '\'') if syntax == Syntax::Yaml && rest[1..].starts_with('\'') => {
+                len += 1;
+            }
+            (Some(open), ch) if ch == open => quote = None,
+            // Strings do not span lines, so a stray quote ends at the line.
+            (Some(_), '\n') => quote = None,
+            (None, '"') => quote = Some(ch),
+            (None, '\'') if syntax != Syntax::Json => quote = Some(ch),
+            _ => {}
+        }
+        output.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    output
+}
+
+fn escape_double_quoted(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Single-quoted scalars have no escapes besides `''`; line breaks would
+/// fold, so control characters become spaces.
+fn escape_single_quoted(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect::<String>()
+        .replace('\'', "''")
+}
+
+/// TOML literal strings have no escapes at all, so a `'` becomes `’` and
+/// control characters become spaces.
+fn escape_literal_string(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| match ch {
+            '\'' => '\u{2019}',
+            ch if ch.is_control() => ' ',
+            ch => ch,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use lore_core::{Document, Value};
+
+    use super::{TemplateVariables, render_markdown_placeholders};
+
+    fn variables() -> TemplateVariables {
+        TemplateVariables {
+            workspace_name: "The Ashen Coast".to_string(),
+            created_at: "2025-03-01T10:00:00+00:00".to_string(),
+            author: "Ada".to_string(),
+            slug: "the-ashen-coast".to_string(),
+        }
+    }
+
+    #[test]
+    fn replaces_known_placeholders() {
+        let rendered =
+            variables().render("# {{workspace_name}}\nby {{ author }} on {{created_at}}");
+
+        assert_eq!(
+            rendered,
+            "# The Ashen Coast\nby Ada on 2025-03-01T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_and_unterminated_placeholders_alone() {
+        let rendered = variables().render("{{mystery}} and {{slug}} and {{unfinished");
+
+        assert_eq!(rendered, "{{mystery}} and the-ashen-coast and {{unfinished");
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_inside_values() {
+        let mut variables = variables();
+        variables.workspace_name = "{{author}}".to_string();
+
+        assert_eq!(variables.render("{{workspace_name}}"), "{{author}}");
+    }
+
+    #[test]
+    fn keeps_rendered_file_names_inside_their_folder() {
+        let mut variables = variables();
+        variables.workspace_name = "../Escape/Plan".to_string();
+
+        assert_eq!(
+            variables.render_file_name("{{workspace_name}}.md"),
+            "..-Escape-Plan.md"
+        );
+        assert_eq!(
+            variables.render_file_name("{{slug}}.lore"),
+            "the-ashen-coast.lore"
+        );
+        assert_eq!(variables.render_file_name(".loreignore"), ".loreignore");
+    }
+
+    #[test]
+    fn escapes_values_rendered_into_frontmatter() {
+        let mut variables = variables();
+        variables.author = "Ana \"La Cuervo\" Ruiz\\C:\\me\n'x'".to_string();
+        let template = "\
+---
+author: \"{{author}}\"
+single: '{{author}}'
+plain: {{author}} # who wrote it
+mixed: by {{author}}
+list: [{{author}}, \"{{slug}}\"]
+items:
+  - {{author}}
+notes: |
+  {{author}}
+---
+# {{author}}
+";
+
+        let rendered = variables.render_file("Index.md", template);
+
+        let document = Document::parse(&rendered).expect("valid frontmatter");
+        let author = Value::from(variables.author.as_str());
+        assert_eq!(document.get("author"), Some(&author));
+        assert_eq!(document.get("plain"), Some(&author));
+        assert_eq!(
+            document.get("single"),
+            Some(&Value::from("Ana \"La Cuervo\" Ruiz\\C:\\me 'x'"))
+        );
+        assert_eq!(
+            document.get("mixed"),
+            Some(&Value::from(format!("by {}", variables.author)))
+        );
+        assert_eq!(
+            document.get("list"),
+            Some(&Value::Sequence(vec![
+                author.clone(),
+                "the-ashen-coast".into()
+            ]))
+        );
+        assert_eq!(
+            document.get("items"),
+            Some(&Value::Sequence(vec![author.clone()]))
+        );
+        assert!(document.body().starts_with("# Ana \"La Cuervo\""));
+        assert!(rendered.contains("author: \"Ana \\\"La Cuervo\\\" Ruiz"));
+    }
+
+    #[test]
+    fn renders_text_without_frontmatter_as_is() {
+        let lookup = |key: &str| (key == "name").then_some("\"Quoted\"");
+
+        assert_eq!(
+            render_markdown_placeholders("# {{name}}\n", lookup),
+            "# \"Quoted\"\n"
+        );
+        assert_eq!(
+            render_markdown_placeholders("---\nname: {{name}}\n", lookup),
+            "---\nname: \"Quoted\"\n"
+        );
+    }
+
+    #[test]
+    fn escapes_values_in_config_files() {
+        let mut variables = variables();
+        variables.workspace_name = "The \"Salt\"\nRoad".to_string();
+        variables.author = "O'Brien, C:\\me".to_string();
+
+        let toml = variables.render_file(
+            "settings.toml",
+            "name = \"{{workspace_name}}\"\nauthor = '{{author}}'\nslug = {{slug}} # by {{author}}\n",
+        );
+        let toml: toml::Table = toml::from_str(&toml).expect("valid toml");
+        assert_eq!(toml["name"].as_str(), Some("The \"Salt\"\nRoad"));
+        assert_eq!(toml["author"].as_str(), Some("O\u{2019}Brien, C:\\me"));
+        assert_eq!(toml["slug"].as_str(), Some("the-ashen-coast"));
+
+        let json = variables.render_file(
+            "state.json",
+            "{\"name\": \"{{workspace_name}}\", \"author\": {{author}}}",
+        );
+        let json: serde_json::Value = serde_json::from_str(&json).expect("valid json");
+        assert_eq!(json["name"], "The \"Salt\"\nRoad");
+        assert_eq!(json["author"], "O'Brien, C:\\me");
+
+        let yaml = variables.render_file(
+            "meta.YML",
+            "name: {{workspace_name}}\nauthor: '{{author}}'\n",
+        );
+        let document = Document::parse(&format!("---\n{yaml}---\n")).expect("valid yaml");
+        assert_eq!(
+            document.get("name"),
+            Some(&Value::from(variables.workspace_name.as_str()))
+        );
+        assert_eq!(
+            document.get("author"),
+            Some(&Value::from(variables.author.as_str()))
+        );
+
+        assert_eq!(
+            variables.render_file("notes.txt", "{{workspace_name}}"),
+            variables.workspace_name
+        );
+    }
+}
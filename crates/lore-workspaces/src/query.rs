@@ -0,0 +1,411 @@
+use std::{cmp::Ordering, path::Path};
+
+use lore_core::{CompareOp, Expr, Operand, Query, Value, extract_links};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    index::{IndexedDocument, WorkspaceIndex, document_title},
+    links::link_target,
+    registry::WorkspaceError,
+};
+
+/// Fields every document has even without frontmatter. A frontmatter key of
+/// the same name wins.
+const NAME_FIELD: &str = "name";
+const TYPE_FIELD: &str = "type";
+const PATH_FIELD: &str = "path";
+const FOLDER_FIELD: &str = "folder";
+
+/// Matching documents as a table: `name` first, then every other field the
+/// query mentions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<QueryRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRow {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub entity_type: Option<String>,
+    /// One per column; `None` where the field is not set.
+    pub values: Vec<Option<Value>>,
+}
+
+/// Runs a query such as `type = character AND faction -> [[Iron Court]]
+/// SORT name` over the frontmatter of every document in the workspace.
+/// See [`Query`] for the syntax.
+pub fn run_query(root: impl AsRef<Path>, source: &str) -> Result<QueryResult, WorkspaceError> {
+    let root = root.as_ref();
+    let query = Query::parse(source)?;
+    let root = root
+        .canonicalize()
+        .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+    let index = WorkspaceIndex::load_cached(&root)?;
+
+    let mut columns = vec![NAME_FIELD.to_string()];
+    for field in query.fields() {
+        if !columns.iter().any(|column| column == field) {
+            columns.push(field.to_string());
+        }
+    }
+
+    let mut matches: Vec<Fields<'_>> = index
+        .documents
+        .iter()
+        .map(|document| Fields::new(&index, document))
+        .filter(|fields| {
+            query
+                .filter
+                .as_ref()
+                .is_none_or(|filter| fields.matches(filter))
+        })
+        .collect();
+
+    // The sort is stable, so ties keep path order.
+    matches.sort_by(|a, b| a.document.path.cmp(&b.document.path));
+    matches.sort_by(|a, b| {
+        query
+            .sort
+            .iter()
+            .map(|key| compare_fields(a.get(&key.field), b.get(&key.field), key.descending))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    if let Some(limit) = query.limit {
+        matches.truncate(limit);
+    }
+
+    let rows = matches
+        .iter()
+        .map(|fields| QueryRow {
+            path: fields.document.path.clone(),
+            entity_type: fields.entity_type.clone(),
+            values: columns.iter().map(|column| fields.get(column)).collect(),
+        })
+        .collect();
+
+    Ok(QueryResult { columns, rows })
+}
+
+/// The fields of one document, as seen by a query.
+struct Fields<'a> {
+    index: &'a WorkspaceIndex,
+    document: &'a IndexedDocument,
+    entity_type: Option<String>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(index: &'a WorkspaceIndex, document: &'a IndexedDocument) -> Self {
+        let entity_type = index
+            .targets
+            .entity_type(&document.path)
+            .map(str::to_string);
+        Self {
+            index,
+            document,
+            entity_type,
+        }
+    }
+
+    fn get(&self, field: &str) -> Option<Value> {
+        let parsed = self.document.parsed();
+        if let Some(value) = parsed.and_then(|document| document.get(field)) {
+            return (!value.is_null()).then(|| value.clone());
+        }
+
+        match field {
+            NAME_FIELD => Some(document_title(self.document.file_name(), parsed).into()),
+            TYPE_FIELD => self.entity_type.clone().map(Value::from),
+            PATH_FIELD => Some(self.document.path.clone().into()),
+            FOLDER_FIELD => Some(
+                self.document
+                    .path
+                    .rsplit_once('/')
+                    .map_or("", |(folder, _)| folder)
+                    .into(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::And(left, right) => self.matches(left) && self.matches(right),
+            Expr::Or(left, right) => self.matches(left) || self.matches(right),
+            Expr::Not(inner) => !self.matches(inner),
+            Expr::Exists(field) => self.get(field).is_some_and(|value| !is_empty(&value)),
+            Expr::Compare { field, op, value } => {
+                let field = self.get(field);
+                match op {
+                    CompareOp::Ne => !self.compare(field.as_ref(), CompareOp::Eq, value),
+                    _ => self.compare(field.as_ref(), *op, value),
+                }
+            }
+        }
+    }
+
+    /// Whether the field, or for lists any of its items, satisfies `op`.
+    fn compare(&self, field: Option<&Value>, op: CompareOp, operand: &Operand) -> bool {
+        let Some(field) = field else {
+            return false;
+        };
+        if let Value::Sequence(items) = field {
+            return items
+                .iter()
+                .any(|item| self.compare(Some(item), op, operand));
+        }
+
+        match (op, operand) {
+            (CompareOp::LinksTo, _) | (CompareOp::Eq, Operand::Link(_)) => {
+                self.links_to(field, operand.as_str())
+            }
+            (CompareOp::Contains, _) => scalar_text(field).is_some_and(|text| {
+                text.to_lowercase()
+                    .contains(&operand.as_str().to_lowercase())
+            }),
+            _ => {
+                let Some(text) = scalar_text(field) else {
+                    return false;
+                };
+                let ordering = compare_text(&text, operand.as_str());
+                match op {
+                    CompareOp::Lt => ordering.is_lt(),
+                    CompareOp::Le => ordering.is_le(),
+                    CompareOp::Gt => ordering.is_gt(),
+                    CompareOp::Ge => ordering.is_ge(),
+                    _ => ordering.is_eq(),
+                }
+            }
+        }
+    }
+
+    /// Whether a frontmatter value links to `target`, comparing the
+    /// documents both resolve to, or the names when `target` is unresolved.
+    fn links_to(&self, value: &Value, target: &str) -> bool {
+        let Some(text) = value.as_str() else {
+            return false;
+        };
+        let links: Vec<String> = match extract_links(text) {
+            links if links.is_empty() => link_target(text).into_iter().collect(),
+            links => links.into_iter().map(|link| link.target).collect(),
+        };
+
+        let targets = &self.index.targets;
+        match targets.resolve(target) {
+            Some(path) => links.iter().any(|link| targets.resolve(link) == Some(path)),
+            None => links.iter().any(|link| link.eq_ignore_ascii_case(target)),
+        }
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Sequence(items) => items.is_empty(),
+        Value::Mapping(mapping) => mapping.is_empty(),
+        _ => false,
+    }
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Numbers compare as numbers, anything else as text ignoring case, so ISO
+/// dates compare in order. Used by filters; sorting uses [`sort_order`].
+fn compare_text(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// The order of a sort, which unlike [`compare_text`] must be total: numbers
+/// come first, in numeric order, then text ignoring case.
+fn sort_order(a: &str, b: &str) -> Ordering {
+    let key = |text: &str| {
+        let number = text.trim().parse::<f64>().ok();
+        (
+            number.is_none(),
+            number.unwrap_or_default(),
+            text.to_lowercase(),
+        )
+    };
+    let (a, b) = (key(a), key(b));
+
+    a.0.cmp(&b.0)
+        .then(a.1.total_cmp(&b.1))
+        .then_with(|| a.2.cmp(&b.2))
+}
+
+/// Unset fields sort last either way; lists sort by their first item.
+fn compare_fields(a: Option<Value>, b: Option<Value>, descending: bool) -> Ordering {
+    let key = |value: Option<Value>| {
+        value.and_then(|value| match value {
+            Value::Sequence(items) => items.first().and_then(scalar_text),
+            value => scalar_text(&value),
+        })
+    };
+
+    match (key(a), key(b)) {
+        (Some(a), Some(b)) if descending => sort_order(&b, &a),
+        (Some(a), Some(b)) => sort_order(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lore_core::Value;
+    use tempfile::tempdir;
+
+    use super::run_query;
+    use crate::registry::WorkspaceError;
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters")).expect("create characters");
+        fs::create_dir_all(root.join("Factions")).expect("create factions");
+        fs::write(
+            root.join("Factions/Iron Court.md"),
+            "---\ntype: faction\naliases: [The Court]\n---\n",
+        )
+        .expect("write court");
+        fs::write(
+            root.join("Characters/Elandra.character.md"),
+            "---\nname: Elandra Vosh\nstatus: active\nage: 34\nfaction: \"[[The Court]]\"\n---\n",
+        )
+        .expect("write elandra");
+        fs::write(
+            root.join("Characters/Oskar.character.md"),
+            "---\nname: Oskar\nstatus: Active\nage: 9\nfaction: Iron Court\ntags: [smuggler, spy]\n---\n",
+        )
+        .expect("write oskar");
+        fs::write(
+            root.join("Characters/Maren.character.md"),
+            "---\nname: Maren Holt\nstatus: retired\nfaction: \"[[Iron Court]]\"\n---\n",
+        )
+        .expect("write maren");
+        temp
+    }
+
+    fn names(root: &std::path::Path, source: &str) -> Vec<String> {
+        run_query(root, source)
+            .expect(source)
+            .rows
+            .into_iter()
+            .map(|row| match &row.values[0] {
+                Some(Value::String(name)) => name.clone(),
+                other => panic!("unexpected name {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filters_on_fields_and_links() {
+        let temp = workspace();
+
+        let result = run_query(
+            temp.path(),
+            "type = character AND status = active AND faction -> [[Iron Court]] SORT name",
+        )
+        .expect("query");
+
+        assert_eq!(result.columns, ["name", "type", "status", "faction"]);
+        let paths: Vec<_> = result.rows.iter().map(|row| row.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "Characters/Elandra.character.md",
+                "Characters/Oskar.character.md"
+            ]
+        );
+        assert_eq!(result.rows[0].entity_type.as_deref(), Some("character"));
+        assert_eq!(result.rows[0].values[1], Some(Value::from("character")));
+    }
+
+    #[test]
+    fn compares_numbers_lists_and_missing_fields() {
+        let temp = workspace();
+        let root = temp.path();
+
+        assert_eq!(names(root, "age > 10"), ["Elandra Vosh"]);
+        assert_eq!(names(root, "age < 10"), ["Oskar"]);
+        assert_eq!(names(root, "tags = spy"), ["Oskar"]);
+        assert_eq!(names(root, "name contains \"holt\""), ["Maren Holt"]);
+        assert_eq!(names(root, "type = character AND NOT age"), ["Maren Holt"]);
+        assert_eq!(
+            names(root, "folder = Characters AND status != active"),
+            ["Maren Holt"]
+        );
+    }
+
+    #[test]
+    fn sorts_and_limits() {
+        let temp = workspace();
+        let root = temp.path();
+
+        assert_eq!(
+            names(root, "type = character SORT age DESC"),
+            ["Elandra Vosh", "Oskar", "Maren Holt"]
+        );
+        assert_eq!(
+            names(root, "SORT type DESC, name LIMIT 2"),
+            ["Iron Court", "Elandra Vosh"]
+        );
+    }
+
+    #[test]
+    fn sorts_numbers_before_text() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        for (name, rank) in [
+            ("A", "rank: \"abc\""),
+            ("B", "rank: 10"),
+            ("C", "rank: Bcd"),
+            ("D", "rank: 9"),
+            ("E", "rank: \"9.5\""),
+            ("F", "note: unranked"),
+        ] {
+            fs::write(
+                root.join(format!("{name}.md")),
+                format!("---\n{rank}\n---\n"),
+            )
+            .expect("write");
+        }
+
+        assert_eq!(names(root, "SORT rank"), ["D", "E", "B", "A", "C", "F"]);
+        assert_eq!(
+            names(root, "SORT rank DESC"),
+            ["C", "A", "B", "E", "D", "F"]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let temp = workspace();
+
+        let error = run_query(temp.path(), "status = ").expect_err("invalid query");
+
+        assert!(matches!(error, WorkspaceError::InvalidQuery(_)));
+        assert_eq!(
+            error.to_string(),
+            "Invalid query: Expected a value (column 10)"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Unpinned entries beyond this count are dropped, oldest first.
+pub const MAX_RECENT_WORKSPACES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspace {
+    /// Workspace UUID from the manifest; absent for entries recorded before ids.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub path: String,
+    pub name: String,
+    pub last_opened: DateTime<Utc>,
+    pub pinned: bool,
+    #[serde(default)]
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecentWorkspaces {
+    entries: Vec<RecentWorkspace>,
+}
+
+impl RecentWorkspaces {
+    /// Moves the workspace to the top of the list, adding it when it is new.
+    /// Entries are matched by workspace id first, so a moved folder updates its
+    /// existing entry instead of leaving a stale one behind. A copied folder
+    /// keeps the id of the original, so while the original still exists the
+    /// copy is matched by path only.
+    pub fn record(&mut self, id: &str, path: &str, name: &str) {
+        self.record_at(id, path, name, Utc::now());
+    }
+
+    pub fn record_at(&mut self, id: &str, path: &str, name: &str, opened_at: DateTime<Utc>) {
+        let path = normalize_path(path);
+        let position = self
+            .entries
+            .iter()
+            .position(|entry| {
+                entry.id.as_deref() == Some(id)
+                    && (normalize_path(&entry.path) == path || !Path::new(&entry.path).is_dir())
+            })
+            .or_else(|| self.position(&path));
+
+        match position {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                entry.id = Some(id.to_string());
+                entry.path = path.clone();
+                entry.name = name.to_string();
+                entry.last_opened = opened_at;
+
+                let mut current = 0;
+                self.entries.retain(|entry| {
+                    let keep = current == index || normalize_path(&entry.path) != path;
+                    current += 1;
+                    keep
+                });
+            }
+            None => self.entries.push(RecentWorkspace {
+                id: Some(id.to_string()),
+                path,
+                name: name.to_string(),
+                last_opened: opened_at,
+                pinned: false,
+                exists: true,
+            }),
+        }
+
+        self.sort();
+        self.trim();
+    }
+
+    /// Returns `false` when `path` is not in the list.
+    pub fn set_pinned(&mut self, path: &str, pinned: bool) -> bool {
+        let Some(index) = self.position(&normalize_path(path)) else {
+            return false;
+        };
+
+        self.entries[index].pinned = pinned;
+        self.sort();
+        self.trim();
+        true
+    }
+
+    /// Returns `false` when `path` is not in the list.
+    pub fn remove(&mut self, path: &str) -> bool {
+        let path = normalize_path(path);
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| normalize_path(&entry.path) != path);
+        self.entries.len() != before
+    }
+
+    /// Forgets every entry, pinned ones included.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Pinned entries first, then most recently opened, with `exists`
+    /// refreshed against the filesystem.
+    pub fn list(&self) -> Vec<RecentWorkspace> {
+        self.entries
+            .iter()
+            .cloned()
+            .map(|mut entry| {
+                entry.exists = Path::new(&entry.path).is_dir();
+                entry
+            })
+            .collect()
+    }
+
+    /// Entries recorded before paths were normalized are compared normalized.
+    fn position(&self, path: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| normalize_path(&entry.path) == path)
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|left, right| {
+            right
+                .pinned
+                .cmp(&left.pinned)
+                .then_with(|| right.last_opened.cmp(&left.last_opened))
+        });
+    }
+
+    fn trim(&mut self) {
+        let mut unpinned = 0;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                return true;
+            }
+
+            unpinned += 1;
+            unpinned <= MAX_RECENT_WORKSPACES
+        });
+    }
+}
+
+/// The canonical form of `path`, so `/lore/atlas` and `/lore/atlas/` are
+/// one entry. Paths that no longer exist are only cleaned up lexically.
+fn normalize_path(path: &str) -> String {
+    let path = Path::new(path);
+    path.canonicalize()
+        .unwrap_or_else(|_| path.components().collect::<PathBuf>())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use tempfile::tempdir;
+
+    use super::{MAX_RECENT_WORKSPACES, RecentWorkspaces};
+
+    fn at(minutes: i64) -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn orders_by_last_opened_and_deduplicates_paths() {
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("a", "/lore/atlas", "Atlas", at(0));
+        recent.record_at("c", "/lore/coast", "Coast", at(1));
+        recent.record_at("a", "/lore/atlas", "Atlas Renamed", at(2));
+
+        let entries = recent.list();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/lore/atlas");
+        assert_eq!(entries[0].name, "Atlas Renamed");
+        assert_eq!(entries[1].path, "/lore/coast");
+    }
+
+    #[test]
+    fn follows_moved_workspace_by_id() {
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("a", "/lore/atlas", "Atlas", at(0));
+        recent.record_at("c", "/lore/coast", "Coast", at(1));
+        recent.set_pinned("/lore/atlas", true);
+
+        recent.record_at("a", "/archive/atlas", "Atlas", at(2));
+
+        let entries = recent.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/archive/atlas");
+        assert!(entries[0].pinned);
+    }
+
+    #[test]
+    fn keeps_copies_apart_from_their_original() {
+        let temp = tempdir().expect("tempdir");
+        let original = temp.path().join("atlas");
+        let copy = temp.path().join("atlas copy");
+        std::fs::create_dir_all(&original).expect("create original");
+        std::fs::create_dir_all(&copy).expect("create copy");
+        let mut recent = RecentWorkspaces::default();
+
+        recent.record_at("a", &original.display().to_string(), "Atlas", at(0));
+        recent.record_at("a", &copy.display().to_string(), "Atlas", at(1));
+
+        let entries = recent.list();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].path.ends_with("atlas copy"));
+        assert!(entries[1].path.ends_with("atlas"));
+    }
+
+    #[test]
+    fn adopts_id_for_entries_recorded_before_ids() {
+        let mut recent: RecentWorkspaces = serde_json::from_str(
+            r#"[{"path":"/lore/atlas","name":"Atlas","lastOpened":"2025-01-01T00:00:00Z","pinned":true}]"#,
+        )
+        .expect("legacy entries");
+
+        recent.record_at("a", "/lore/atlas", "Atlas", at(5));
+
+        let entries = recent.list();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id.as_deref(), Some("a"));
+        assert!(entries[0].pinned);
+    }
+
+    #[test]
+    fn keeps_pinned_entries_first_until_cleared() {
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("a", "/lore/atlas", "Atlas", at(0));
+        recent.record_at("c", "/lore/coast", "Coast", at(1));
+
+        assert!(recent.set_pinned("/lore/atlas", true));
+        assert_eq!(recent.list()[0].path, "/lore/atlas");
+
+        recent.clear();
+        assert!(recent.list().is_empty());
+    }
+
+    #[test]
+    fn normalizes_recorded_paths() {
+        let temp = tempdir().expect("tempdir");
+        let canonical = temp.path().canonicalize().expect("canonical");
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("a", &format!("{}/", temp.path().display()), "Atlas", at(0));
+        recent.record_at("b", "/lore/./coast/", "Coast", at(1));
+        recent.record_at("c", "/lore/coast", "Coast Copy", at(2));
+
+        let entries = recent.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/lore/coast");
+        assert_eq!(entries[1].path, canonical.display().to_string());
+        assert!(recent.set_pinned(&temp.path().display().to_string(), true));
+        assert!(recent.remove("/lore/coast/"));
+    }
+
+    #[test]
+    fn caps_unpinned_entries() {
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("p", "/lore/pinned", "Pinned", at(0));
+        recent.set_pinned("/lore/pinned", true);
+
+        for index in 0..MAX_RECENT_WORKSPACES + 5 {
+            let path = format!("/lore/{index}");
+            recent.record_at(&index.to_string(), &path, "Workspace", at(index as i64 + 1));
+        }
+
+        let entries = recent.list();
+        assert_eq!(entries.len(), MAX_RECENT_WORKSPACES + 1);
+        assert_eq!(entries[0].path, "/lore/pinned");
+        assert!(!entries.iter().any(|entry| entry.path == "/lore/0"));
+    }
+
+    #[test]
+    fn reports_missing_folders_and_removes_entries() {
+        let temp = tempdir().expect("tempdir");
+        let existing = temp.path().display().to_string();
+        let mut recent = RecentWorkspaces::default();
+        recent.record_at("h", &existing, "Here", at(0));
+        recent.record_at("g", "/definitely/not/here", "Gone", at(1));
+
+        let entries = recent.list();
+        assert!(!entries[0].exists);
+        assert!(entries[1].exists);
+
+        assert!(recent.remove("/definitely/not/here"));
+        assert!(!recent.remove("/definitely/not/here"));
+        assert_eq!(recent.list().len(), 1);
+    }
+}
@@ -3,37 +3,68 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use chrono::Utc;
-use serde::Serialize;
 use thiserror::Error;
 
-use crate::models::{
-    CreateWorkspaceRequest, CreateWorkspaceResult, WorkspaceTemplateSummary, WorkspaceVersion,
+use crate::{
+    models::{CreateWorkspaceRequest, CreateWorkspaceResult, WorkspaceTemplateSummary},
+    scaffold::{
+        NewWorkspace, create_blank_workspace, create_game_bible_workspace, create_novel_workspace,
+        create_sample_workspace,
+    },
+    user_templates::{UserTemplate, load_user_templates},
 };
 
-const INTERNAL_DIR: &str = ".lore";
-const SETTINGS_FILE: &str = "settings.toml";
-const STATE_FILE: &str = "state.json";
+pub(crate) const INTERNAL_DIR: &str = ".lore";
+pub(crate) const SETTINGS_FILE: &str = "settings.toml";
+pub(crate) const STATE_FILE: &str = "state.json";
+pub(crate) const CACHE_DIR: &str = "cache";
+pub(crate) const TRASH_DIR: &str = "trash";
 
 pub fn list_workspace_templates() -> Vec<WorkspaceTemplateSummary> {
-    builtin_templates()
-        .iter()
-        .map(|definition| definition.summary())
-        .collect()
+    list_templates(None)
+}
+
+/// Lists the built-in templates plus the user templates in `user_templates_dir`.
+pub fn list_workspace_templates_from(user_templates_dir: &Path) -> Vec<WorkspaceTemplateSummary> {
+    list_templates(Some(user_templates_dir))
 }
 
 pub fn create_workspace(
     request: CreateWorkspaceRequest,
 ) -> Result<CreateWorkspaceResult, WorkspaceError> {
-    let definition = builtin_templates()
+    create_workspace_with(request, None)
+}
+
+/// Like [`create_workspace`], but can also instantiate the user templates in
+/// `user_templates_dir`.
+pub fn create_workspace_from(
+    request: CreateWorkspaceRequest,
+    user_templates_dir: &Path,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    create_workspace_with(request, Some(user_templates_dir))
+}
+
+fn list_templates(user_templates_dir: Option<&Path>) -> Vec<WorkspaceTemplateSummary> {
+    let mut summaries: Vec<_> = available_templates(user_templates_dir)
+        .iter()
+        .map(WorkspaceTemplate::summary)
+        .collect();
+    summaries.sort_by_key(|summary| summary.sort_order);
+    summaries
+}
+
+fn create_workspace_with(
+    request: CreateWorkspaceRequest,
+    user_templates_dir: Option<&Path>,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let template = available_templates(user_templates_dir)
         .into_iter()
-        .find(|definition| definition.id == request.template_id)
+        .find(|template| template.id() == request.template_id)
         .ok_or_else(|| WorkspaceError::UnknownTemplate(request.template_id.clone()))?;
 
-    if !definition.supports_creation {
-        return Err(WorkspaceError::TemplateUnavailable(
-            definition.id.to_string(),
-        ));
+    let summary = template.summary();
+    if !summary.supports_creation {
+        return Err(WorkspaceError::TemplateUnavailable(summary.id));
     }
 
     let validated_name = validate_name(&request.name)?;
@@ -42,7 +73,41 @@ pub fn create_workspace(
 
     ensure_destination_ready(&root_path)?;
 
-    definition.create(validated_name, &root_path, &request.app_version)
+    template.create(&NewWorkspace {
+        name: validated_name,
+        root_path: &root_path,
+        app_version: &request.app_version,
+        author: request
+            .author
+            .as_deref()
+            .map(str::trim)
+            .filter(|author| !author.is_empty()),
+    })
+}
+
+pub(crate) fn is_builtin_template(id: &str) -> bool {
+    builtin_templates()
+        .iter()
+        .any(|definition| definition.id == id)
+}
+
+fn available_templates(user_templates_dir: Option<&Path>) -> Vec<WorkspaceTemplate> {
+    let builtins = builtin_templates();
+    let mut templates: Vec<_> = builtins
+        .into_iter()
+        .map(WorkspaceTemplate::Builtin)
+        .collect();
+
+    if let Some(user_templates_dir) = user_templates_dir {
+        let reserved_ids: Vec<&str> = builtins.iter().map(|definition| definition.id).collect();
+        templates.extend(
+            load_user_templates(user_templates_dir, &reserved_ids)
+                .into_iter()
+                .map(WorkspaceTemplate::User),
+        );
+    }
+
+    templates
 }
 
 #[derive(Debug, Error)]
@@ -62,13 +127,91 @@ pub enum WorkspaceError {
     #[error("Template '{0}' is not available for creation yet.")]
     TemplateUnavailable(String),
 
+    #[error("The template file '{path}' is malformed: {reason}")]
+    MalformedTemplate { path: String, reason: String },
+
+    #[error("The entity type file '{path}' is malformed: {reason}")]
+    MalformedEntityType { path: String, reason: String },
+
+    #[error("Template name cannot be empty.")]
+    EmptyTemplateName,
+
+    #[error("A template with the id '{0}' already exists.")]
+    TemplateExists(String),
+
     #[error("A file already exists at '{0}'.")]
     DestinationIsFile(String),
 
+    #[error("A folder already exists at '{0}'.")]
+    DestinationIsFolder(String),
+
     #[error("The destination folder '{0}' already exists and is not empty.")]
     DestinationNotEmpty(String),
 
-    #[error("Unable to create workspace: {0}")]
+    #[error("No workspace found at '{0}'.")]
+    WorkspaceNotFound(String),
+
+    #[error("No workspace manifest (*.lore) found in '{0}'.")]
+    MissingManifest(String),
+
+    #[error("Found more than one workspace manifest: {0}.")]
+    DuplicateManifest(String),
+
+    #[error("The workspace manifest '{path}' is malformed: {reason}")]
+    MalformedManifest { path: String, reason: String },
+
+    #[error("The workspace settings file '{path}' is malformed: {reason}")]
+    MalformedSettings { path: String, reason: String },
+
+    #[error("'{0}' must be a folder for the workspace to open.")]
+    CorruptInternalDir(String),
+
+    #[error(
+        "This workspace uses format version {found}, but this version of Lore Designer only supports up to {supported}. Please update the app."
+    )]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("No migration is available from workspace format version {0}.")]
+    MissingMigration(u32),
+
+    #[error("Migration from workspace format version {from} ({description}) failed: {reason}")]
+    MigrationFailed {
+        from: u32,
+        description: String,
+        reason: String,
+    },
+
+    #[error("'{0}' is outside the workspace.")]
+    PathOutsideWorkspace(String),
+
+    #[error("'{0}' does not exist.")]
+    PathNotFound(String),
+
+    #[error("Item '{0}' is not in the trash.")]
+    TrashItemNotFound(String),
+
+    #[error("Entity name cannot be empty.")]
+    EmptyEntityName,
+
+    #[error("'{0}' is not an entity type of this workspace.")]
+    UnknownEntityType(String),
+
+    #[error("Document '{0}' does not exist.")]
+    DocumentNotFound(String),
+
+    #[error("'{0}' changed on disk since it was opened. Reload it or save again to overwrite.")]
+    DocumentConflict(String),
+
+    #[error("Could not watch the workspace for changes: {0}")]
+    Watch(#[from] notify::Error),
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(#[from] lore_core::QueryError),
+
+    #[error("Search index error: {0}")]
+    Search(#[from] tantivy::TantivyError),
+
+    #[error("Workspace filesystem error: {0}")]
     Io(#[from] io::Error),
 }
 
@@ -79,7 +222,7 @@ struct TemplateDefinition {
     description: &'static str,
     supports_creation: bool,
     sort_order: u16,
-    create_fn: fn(&str, &Path, &str) -> Result<CreateWorkspaceResult, WorkspaceError>,
+    create_fn: fn(&NewWorkspace<'_>) -> Result<CreateWorkspaceResult, WorkspaceError>,
 }
 
 impl TemplateDefinition {
@@ -94,13 +237,39 @@ impl TemplateDefinition {
         }
     }
 
+    fn create(self, workspace: &NewWorkspace<'_>) -> Result<CreateWorkspaceResult, WorkspaceError> {
+        (self.create_fn)(workspace)
+    }
+}
+
+enum WorkspaceTemplate {
+    Builtin(TemplateDefinition),
+    User(UserTemplate),
+}
+
+impl WorkspaceTemplate {
+    fn id(&self) -> &str {
+        match self {
+            Self::Builtin(definition) => definition.id,
+            Self::User(template) => &template.file.id,
+        }
+    }
+
+    fn summary(&self) -> WorkspaceTemplateSummary {
+        match self {
+            Self::Builtin(definition) => definition.summary(),
+            Self::User(template) => template.summary(),
+        }
+    }
+
     fn create(
-        self,
-        name: &str,
-        root_path: &Path,
-        app_version: &str,
+        &self,
+        workspace: &NewWorkspace<'_>,
     ) -> Result<CreateWorkspaceResult, WorkspaceError> {
-        (self.create_fn)(name, root_path, app_version)
+        match self {
+            Self::Builtin(definition) => definition.create(workspace),
+            Self::User(template) => template.create(workspace),
+        }
     }
 }
 
@@ -118,73 +287,29 @@ fn builtin_templates() -> [TemplateDefinition; 4] {
             id: "novel",
             display_name: "Long-form novel",
             description: "Pre-organized for chapters, character files, and a places index.",
-            supports_creation: false,
+            supports_creation: true,
             sort_order: 1,
-            create_fn: unsupported_template,
+            create_fn: create_novel_workspace,
         },
         TemplateDefinition {
             id: "game-bible",
             display_name: "Game / narrative bible",
             description: "Factions, regions, and quest-style threads for ongoing development.",
-            supports_creation: false,
+            supports_creation: true,
             sort_order: 2,
-            create_fn: unsupported_template,
+            create_fn: create_game_bible_workspace,
         },
         TemplateDefinition {
             id: "sample",
             display_name: "Sample — Saltreach Cycle",
             description: "A small worked example showing how characters, places, and drafts link together.",
-            supports_creation: false,
+            supports_creation: true,
             sort_order: 3,
-            create_fn: unsupported_template,
+            create_fn: create_sample_workspace,
         },
     ]
 }
 
-fn unsupported_template(
-    _name: &str,
-    _root_path: &Path,
-    app_version: &str,
-) -> Result<CreateWorkspaceResult, WorkspaceError> {
-    Err(WorkspaceError::TemplateUnavailable(app_version.to_string()))
-}
-
-fn create_blank_workspace(
-    name: &str,
-    root_path: &Path,
-    app_version: &str,
-) -> Result<CreateWorkspaceResult, WorkspaceError> {
-    fs::create_dir_all(root_path)?;
-
-    let internal_dir = root_path.join(INTERNAL_DIR);
-    fs::create_dir_all(&internal_dir)?;
-
-    let manifest_name = format!("{}.lore", slugify_project_name(name));
-    let manifest_path = root_path.join(manifest_name);
-    let created_at = Utc::now();
-
-    let manifest = WorkspaceManifest {
-        name,
-        workspace_version: WorkspaceVersion::default().major,
-        created_with: app_version,
-        created_at: created_at.to_rfc3339(),
-        template_id: "blank",
-    };
-
-    fs::write(&manifest_path, toml::to_string_pretty(&manifest)?)?;
-    fs::write(internal_dir.join(SETTINGS_FILE), "")?;
-    fs::write(internal_dir.join(STATE_FILE), "{}\n")?;
-
-    Ok(CreateWorkspaceResult {
-        name: name.to_string(),
-        root_path: root_path.display().to_string(),
-        manifest_path: manifest_path.display().to_string(),
-        created_at: created_at.to_rfc3339(),
-        template_id: "blank".to_string(),
-        workspace_version: WorkspaceVersion::default(),
-    })
-}
-
 fn validate_name(name: &str) -> Result<&str, WorkspaceError> {
     let trimmed = name.trim();
     if trimmed.is_empty() {
@@ -227,7 +352,7 @@ fn ensure_destination_ready(path: &Path) -> Result<(), WorkspaceError> {
     }
 }
 
-fn slugify_project_name(name: &str) -> String {
+pub(crate) fn slugify_project_name(name: &str) -> String {
     let mut slug = String::new();
     let mut last_was_separator = false;
 
@@ -252,16 +377,6 @@ fn slugify_project_name(name: &str) -> String {
     }
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "snake_case")]
-struct WorkspaceManifest<'a> {
-    name: &'a str,
-    workspace_version: u32,
-    created_with: &'a str,
-    created_at: String,
-    template_id: &'a str,
-}
-
 impl From<toml::ser::Error> for WorkspaceError {
     fn from(error: toml::ser::Error) -> Self {
         WorkspaceError::Io(io::Error::other(error))
@@ -284,7 +399,10 @@ mod tests {
         assert_eq!(templates.len(), 4);
         assert_eq!(templates[0].id, "blank");
         assert!(templates[0].supports_creation);
-        assert!(!templates[1].supports_creation);
+        assert_eq!(templates[1].id, "novel");
+        assert!(templates[1].supports_creation);
+        assert!(templates[2].supports_creation);
+        assert!(templates[3].supports_creation);
     }
 
     #[test]
@@ -298,6 +416,7 @@ mod tests {
             parent_path: parent_path.display().to_string(),
             template_id: "blank".to_string(),
             app_version: "0.1.0".to_string(),
+            author: None,
         })
         .expect("workspace created");
 
@@ -305,11 +424,132 @@ mod tests {
         assert!(root_path.join(".lore/settings.toml").is_file());
         assert!(root_path.join(".lore/state.json").is_file());
         assert!(root_path.join("the-ashen-coast.lore").is_file());
+        assert!(root_path.join(".loreignore").is_file());
         assert_eq!(result.template_id, "blank");
         assert_eq!(result.workspace_version.major, 1);
         assert_eq!(result.root_path, root_path.display().to_string());
     }
 
+    #[test]
+    fn creates_novel_workspace_scaffold() {
+        let temp = tempdir().expect("tempdir");
+        let parent_path = temp.path().join("Lore");
+        let root_path = parent_path.join("the-salt-road");
+
+        let result = create_workspace(CreateWorkspaceRequest {
+            name: "The Salt Road".to_string(),
+            parent_path: parent_path.display().to_string(),
+            template_id: "novel".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created");
+
+        assert!(root_path.join(".lore/settings.toml").is_file());
+        assert!(root_path.join("Chapters").is_dir());
+        assert!(root_path.join("Characters").is_dir());
+        assert!(root_path.join("Places").is_dir());
+        assert!(root_path.join(".loreignore").is_file());
+        assert_eq!(result.template_id, "novel");
+
+        let chapter =
+            fs::read_to_string(root_path.join("Chapters/Chapter 01.md")).expect("read chapter");
+        assert!(chapter.starts_with("---\ntype: chapter\n"));
+        let index = fs::read_to_string(root_path.join("Index.md")).expect("read index");
+        assert!(index.contains("# The Salt Road"));
+        assert!(index.contains(&format!("created: \"{}\"", result.created_at)));
+        let manifest =
+            fs::read_to_string(root_path.join("the-salt-road.lore")).expect("read manifest");
+        assert!(manifest.contains("template_id = \"novel\""));
+    }
+
+    #[test]
+    fn escapes_author_in_novel_frontmatter() {
+        let temp = tempdir().expect("tempdir");
+        let parent_path = temp.path().join("Lore");
+        let author = "Ana \"La Cuervo\" Ruiz, C:\\me";
+
+        let result = create_workspace(CreateWorkspaceRequest {
+            name: "The Salt Road".to_string(),
+            parent_path: parent_path.display().to_string(),
+            template_id: "novel".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: Some(author.to_string()),
+        })
+        .expect("workspace created");
+
+        let index = fs::read_to_string(std::path::Path::new(&result.root_path).join("Index.md"))
+            .expect("read index");
+        let document = lore_core::Document::parse(&index).expect("valid frontmatter");
+        assert_eq!(document.get("author"), Some(&author.into()));
+    }
+
+    #[test]
+    fn creates_game_bible_workspace_scaffold() {
+        let temp = tempdir().expect("tempdir");
+        let parent_path = temp.path().join("Lore");
+        let root_path = parent_path.join("hollow-tide");
+
+        let result = create_workspace(CreateWorkspaceRequest {
+            name: "Hollow Tide".to_string(),
+            parent_path: parent_path.display().to_string(),
+            template_id: "game-bible".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created");
+
+        for folder in ["Factions", "Regions", "Quests", "Characters", "Items"] {
+            assert!(root_path.join(folder).is_dir(), "{folder} missing");
+        }
+        assert_eq!(result.template_id, "game-bible");
+
+        let faction = fs::read_to_string(root_path.join("Factions/Iron Court.faction.md"))
+            .expect("read faction");
+        assert!(faction.starts_with("---\ntype: faction\n"));
+        let quest = fs::read_to_string(root_path.join("Quests/The Drowned Bell.quest.md"))
+            .expect("read quest");
+        assert!(quest.starts_with("---\ntype: quest\n"));
+
+        for schema in ["faction", "region", "quest", "item"] {
+            let path = root_path.join(format!(".lore/types/{schema}.toml"));
+            let contents = fs::read_to_string(&path).expect("read schema");
+            let table: toml::Table = contents.parse().expect("valid schema toml");
+            assert_eq!(table["id"].as_str(), Some(schema));
+        }
+    }
+
+    #[test]
+    fn creates_sample_workspace_from_embedded_files() {
+        let temp = tempdir().expect("tempdir");
+        let parent_path = temp.path().join("Lore");
+        let root_path = parent_path.join("my-first-world");
+
+        let result = create_workspace(CreateWorkspaceRequest {
+            name: "My First World".to_string(),
+            parent_path: parent_path.display().to_string(),
+            template_id: "sample".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created");
+
+        assert_eq!(result.template_id, "sample");
+        assert!(root_path.join(".loreignore").is_file());
+        assert!(
+            root_path
+                .join("Characters/Maren Holt.character.md")
+                .is_file()
+        );
+        assert!(root_path.join("Places/Saltreach.location.md").is_file());
+        assert!(root_path.join("Drafts/01 - Low Tide.md").is_file());
+
+        let manifest =
+            fs::read_to_string(root_path.join("my-first-world.lore")).expect("read manifest");
+        assert!(manifest.contains("name = \"My First World\""));
+        assert!(manifest.contains("template_id = \"sample\""));
+    }
+
     #[test]
     fn rejects_existing_non_empty_directory() {
         let temp = tempdir().expect("tempdir");
@@ -323,6 +563,7 @@ mod tests {
             parent_path: parent_path.display().to_string(),
             template_id: "blank".to_string(),
             app_version: "0.1.0".to_string(),
+            author: None,
         })
         .expect_err("must fail");
 
@@ -343,6 +584,7 @@ mod tests {
             parent_path: parent_path.display().to_string(),
             template_id: "mystery".to_string(),
             app_version: "0.1.0".to_string(),
+            author: None,
         })
         .expect_err("must fail");
 
@@ -360,6 +602,7 @@ mod tests {
             parent_path: parent_path.display().to_string(),
             template_id: "blank".to_string(),
             app_version: "0.1.0".to_string(),
+            author: None,
         })
         .expect("workspace created");
 
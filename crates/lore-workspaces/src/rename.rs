@@ -0,0 +1,561 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use lore_core::{Document, Mapping, Value, WikiLink, extract_links};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::{resolve_workspace_path, write_atomic},
+    index::WorkspaceIndex,
+    links::link_target,
+    registry::WorkspaceError,
+    scan::{entity_name, relative_path},
+    schema::{EntityTypes, FieldKind},
+    trash::move_path,
+};
+
+/// One link rewritten by a rename.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkEdit {
+    /// The edited file, at its path after the rename.
+    pub path: String,
+    /// The frontmatter key holding the link; `None` for links in the body.
+    pub field: Option<String>,
+    /// Position of the link before the edit, counting from 1. For
+    /// frontmatter links, the line of their key.
+    pub line: usize,
+    pub column: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    pub from: String,
+    pub to: String,
+    pub edits: Vec<LinkEdit>,
+    /// `false` for a dry run: nothing was written.
+    pub applied: bool,
+}
+
+/// Moves the document at `from` to `to` and rewrites every wiki-link and
+/// frontmatter link field that pointed to it by file name or path. Links by
+/// frontmatter `name` or alias still resolve and are left alone.
+///
+/// With `dry_run`, only reports the edits. Otherwise all files are written
+/// or none are: if any write fails, the ones already made are undone.
+pub fn rename_entity(
+    root: impl AsRef<Path>,
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    dry_run: bool,
+) -> Result<RenameResult, WorkspaceError> {
+    let (root, from_path) = resolve_workspace_path(root.as_ref(), from.as_ref())?;
+    let (_, to_path) = resolve_workspace_path(&root, to.as_ref())?;
+    let from = relative_path(&root, &from_path);
+    let to = relative_path(&root, &to_path);
+
+    if !from_path.is_file() {
+        return Err(WorkspaceError::DocumentNotFound(from));
+    }
+    let is_same_file = to_path
+        .canonicalize()
+        .is_ok_and(|existing| existing == from_path);
+    if to_path.is_dir() {
+        return Err(WorkspaceError::DestinationIsFolder(to));
+    }
+    if to_path.exists() && !is_same_file {
+        return Err(WorkspaceError::DestinationIsFile(to));
+    }
+
+    let index = WorkspaceIndex::load(&root)?;
+    let retarget = Retarget::new(&index, &from, &to);
+
+    let mut edits = Vec::new();
+    let mut writes = Vec::new();
+    for indexed in &index.documents {
+        let final_path = if indexed.path == from {
+            &to
+        } else {
+            &indexed.path
+        };
+        let original = fs::read_to_string(root.join(&indexed.path))?;
+        let Some((content, file_edits)) =
+            rewrite_document(&original, final_path, &index.types, &retarget)?
+        else {
+            continue;
+        };
+
+        edits.extend(file_edits);
+        writes.push((root.join(final_path), original, content));
+    }
+
+    if !dry_run {
+        apply(&from_path, &to_path, &writes)?;
+    }
+
+    Ok(RenameResult {
+        from,
+        to,
+        edits,
+        applied: !dry_run,
+    })
+}
+
+/// Moves the file, then writes the edits, undoing everything on failure.
+fn apply(
+    from: &Path,
+    to: &Path,
+    writes: &[(PathBuf, String, String)],
+) -> Result<(), WorkspaceError> {
+    if from != to {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(from, to)?;
+    }
+
+    for (written, (path, _, content)) in writes.iter().enumerate() {
+        if let Err(error) = write_atomic(path, content.as_bytes()) {
+            for (path, original, _) in &writes[..written] {
+                let _ = write_atomic(path, original.as_bytes());
+            }
+            if from != to {
+                let _ = move_path(to, from);
+            }
+            return Err(error.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Decides the new target of links that pointed to the renamed file.
+struct Retarget<'a> {
+    index: &'a WorkspaceIndex,
+    from: &'a str,
+    old_name: String,
+    old_path: String,
+    new_name: String,
+    new_path: String,
+}
+
+impl<'a> Retarget<'a> {
+    fn new(index: &'a WorkspaceIndex, from: &'a str, to: &str) -> Self {
+        let (old_name, old_path) = link_forms(from);
+        let (new_name, new_path) = link_forms(to);
+
+        // Another document answering to the new name would capture links.
+        let is_taken = index
+            .targets
+            .resolve(&new_name)
+            .is_some_and(|path| path != from);
+        let new_name = if is_taken { new_path.clone() } else { new_name };
+
+        Self {
+            index,
+            from,
+            old_name,
+            old_path,
+            new_name,
+            new_path,
+        }
+    }
+
+    fn target_for(&self, target: &str) -> Option<&str> {
+        if self.index.targets.resolve(target) != Some(self.from) {
+            return None;
+        }
+
+        let target = target.trim();
+        if target.eq_ignore_ascii_case(&self.old_path) && self.old_path != self.old_name {
+            Some(&self.new_path)
+        } else if target.to_lowercase() == self.old_name.to_lowercase() {
+            Some(&self.new_name)
+        } else {
+            None
+        }
+    }
+}
+
+/// `Maren` and `Characters/Maren` for `Characters/Maren.character.md`.
+fn link_forms(path: &str) -> (String, String) {
+    let (folder, file_name) = match path.rsplit_once('/') {
+        Some((folder, file_name)) => (Some(folder), file_name),
+        None => (None, path),
+    };
+    let name = entity_name(file_name).to_string();
+    let path = match folder {
+        Some(folder) => format!("{folder}/{name}"),
+        None => name.clone(),
+    };
+
+    (name, path)
+}
+
+/// The rewritten content and its edits, or `None` when nothing changes.
+/// Files whose frontmatter cannot be parsed are rewritten as plain text.
+fn rewrite_document(
+    content: &str,
+    path: &str,
+    types: &EntityTypes,
+    retarget: &Retarget,
+) -> Result<Option<(String, Vec<LinkEdit>)>, WorkspaceError> {
+    let mut document =
+        Document::parse(content).unwrap_or_else(|_| Document::new(Mapping::new(), content));
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let schema = types.for_document(file_name, Some(&document));
+    let mut edits = Vec::new();
+
+    let mut changed_fields = Vec::new();
+    for (key, value) in document.frontmatter() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        let is_link_field = schema
+            .and_then(|schema| schema.field(key))
+            .is_some_and(|field| field.value_kind() == FieldKind::Link);
+
+        let mut replaced = Vec::new();
+        if let Some(value) = rewrite_value(value, is_link_field, retarget, &mut replaced) {
+            let line = document.key_line(key).unwrap_or(1);
+            edits.extend(replaced.into_iter().map(|(before, after)| LinkEdit {
+                path: path.to_string(),
+                field: Some(key.to_string()),
+                line,
+                column: 1,
+                before,
+                after,
+            }));
+            changed_fields.push((key.to_string(), value));
+        }
+    }
+    for (key, value) in changed_fields {
+        document.set(&key, value);
+    }
+
+    let body_line = document.body_line();
+    let mut body = document.body().to_string();
+    let mut replacements = Vec::new();
+    for link in extract_links(&body) {
+        let Some(target) = retarget.target_for(&link.target) else {
+            continue;
+        };
+        let after = link_text(&link, target);
+        edits.push(LinkEdit {
+            path: path.to_string(),
+            field: None,
+            line: body_line + link.line - 1,
+            column: link.column,
+            before: body[link.span.clone()].to_string(),
+            after: after.clone(),
+        });
+        replacements.push((link.span, after));
+    }
+    for (span, after) in replacements.into_iter().rev() {
+        body.replace_range(span, &after);
+    }
+
+    if edits.is_empty() {
+        return Ok(None);
+    }
+
+    document.set_body(body);
+    let content = document
+        .to_markdown()
+        .map_err(|error| WorkspaceError::Io(io::Error::other(error)))?;
+    Ok(Some((content, edits)))
+}
+
+/// The value with its links to the renamed file rewritten, or `None` when
+/// it has none. Each rewrite is recorded in `edits` as `(before, after)`.
+fn rewrite_value(
+    value: &Value,
+    is_link_field: bool,
+    retarget: &Retarget,
+    edits: &mut Vec<(String, String)>,
+) -> Option<Value> {
+    match value {
+        Value::String(text) => {
+            let links = extract_links(text);
+            if links.is_empty() {
+                let target = link_target(text).filter(|_| is_link_field)?;
+                let after = retarget.target_for(&target)?.to_string();
+                edits.push((text.clone(), after.clone()));
+                return Some(Value::String(after));
+            }
+
+            let mut replacements = Vec::new();
+            for link in links {
+                if let Some(target) = retarget.target_for(&link.target) {
+                    let after = link_text(&link, target);
+                    edits.push((text[link.span.clone()].to_string(), after.clone()));
+                    replacements.push((link.span, after));
+                }
+            }
+            if replacements.is_empty() {
+                return None;
+            }
+
+            let mut rewritten = text.clone();
+            for (span, after) in replacements.into_iter().rev() {
+                rewritten.replace_range(span, &after);
+            }
+            Some(Value::String(rewritten))
+        }
+        Value::Sequence(items) => {
+            let mut changed = false;
+            let items = items
+                .iter()
+                .map(
+                    |item| match rewrite_value(item, is_link_field, retarget, edits) {
+                        Some(item) => {
+                            changed = true;
+                            item
+                        }
+                        None => item.clone(),
+                    },
+                )
+                .collect();
+            changed.then_some(Value::Sequence(items))
+        }
+        Value::Mapping(mapping) => {
+            let mut changed = false;
+            let mapping = mapping
+                .iter()
+                .map(
+                    |(key, item)| match rewrite_value(item, false, retarget, edits) {
+                        Some(item) => {
+                            changed = true;
+                            (key.clone(), item)
+                        }
+                        None => (key.clone(), item.clone()),
+                    },
+                )
+                .collect();
+            changed.then_some(Value::Mapping(mapping))
+        }
+        _ => None,
+    }
+}
+
+/// `link` written again with a new target, keeping its heading and alias.
+fn link_text(link: &WikiLink, target: &str) -> String {
+    let mut text = String::new();
+    if link.embed {
+        text.push('!');
+    }
+    text.push_str("[[");
+    text.push_str(target);
+    if let Some(heading) = &link.heading {
+        text.push('#');
+        text.push_str(heading);
+    }
+    if let Some(alias) = &link.alias {
+        text.push('|');
+        text.push_str(alias);
+    }
+    text.push_str("]]");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{apply, rename_entity};
+    use crate::registry::WorkspaceError;
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters")).expect("create characters");
+        fs::write(
+            root.join("Characters/Elandra.character.md"),
+            "---\nname: Elandra Vosh\nallies:\n  - \"[[Oskar]]\"\n---\nSee [[Elandra#Youth]].\n",
+        )
+        .expect("write elandra");
+        fs::write(
+            root.join("Characters/Oskar.character.md"),
+            "---\n# Oskar's allies\nname: Oskar\nallies: [\"[[Elandra]]\", Maren]\n---\nOskar trusts [[elandra|her]], [[Characters/Elandra]] and [[Elandra Vosh]].\n",
+        )
+        .expect("write oskar");
+        temp
+    }
+
+    #[test]
+    fn dry_run_reports_edits_without_writing() {
+        let temp = workspace();
+        let before =
+            fs::read_to_string(temp.path().join("Characters/Oskar.character.md")).expect("read");
+
+        let result = rename_entity(
+            temp.path(),
+            "Characters/Elandra.character.md",
+            "Characters/Elandra Vosh.character.md",
+            true,
+        )
+        .expect("planned");
+
+        assert!(!result.applied);
+        let edits: Vec<_> = result
+            .edits
+            .iter()
+            .map(|edit| {
+                (
+                    edit.path.as_str(),
+                    edit.field.as_deref(),
+                    edit.before.as_str(),
+                    edit.after.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edits,
+            [
+                (
+                    "Characters/Elandra Vosh.character.md",
+                    None,
+                    "[[Elandra#Youth]]",
+                    "[[Elandra Vosh#Youth]]"
+                ),
+                (
+                    "Characters/Oskar.character.md",
+                    Some("allies"),
+                    "[[Elandra]]",
+                    "[[Elandra Vosh]]"
+                ),
+                (
+                    "Characters/Oskar.character.md",
+                    None,
+                    "[[elandra|her]]",
+                    "[[Elandra Vosh|her]]"
+                ),
+                (
+                    "Characters/Oskar.character.md",
+                    None,
+                    "[[Characters/Elandra]]",
+                    "[[Characters/Elandra Vosh]]"
+                ),
+            ]
+        );
+        assert!(temp.path().join("Characters/Elandra.character.md").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("Characters/Oskar.character.md")).expect("read"),
+            before
+        );
+    }
+
+    #[test]
+    fn moves_file_and_rewrites_links() {
+        let temp = workspace();
+
+        let result = rename_entity(
+            temp.path(),
+            "Characters/Elandra.character.md",
+            "Cast/Queen.character.md",
+            false,
+        )
+        .expect("renamed");
+
+        assert!(result.applied);
+        assert!(!temp.path().join("Characters/Elandra.character.md").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("Cast/Queen.character.md")).expect("read"),
+            "---\nname: Elandra Vosh\nallies:\n  - \"[[Oskar]]\"\n---\nSee [[Queen#Youth]].\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("Characters/Oskar.character.md")).expect("read"),
+            "---\n# Oskar's allies\nname: Oskar\nallies:\n- '[[Queen]]'\n- Maren\n---\nOskar trusts [[Queen|her]], [[Cast/Queen]] and [[Elandra Vosh]].\n"
+        );
+    }
+
+    #[test]
+    fn rewrites_bare_names_in_link_fields() {
+        let temp = workspace();
+        fs::write(
+            temp.path().join("Characters/Maren.character.md"),
+            "---\nname: Maren\n---\n",
+        )
+        .expect("write maren");
+
+        let result = rename_entity(
+            temp.path(),
+            "Characters/Maren.character.md",
+            "Characters/Maren Holt.character.md",
+            false,
+        )
+        .expect("renamed");
+
+        assert_eq!(result.edits.len(), 1);
+        let oskar =
+            fs::read_to_string(temp.path().join("Characters/Oskar.character.md")).expect("read");
+        assert!(oskar.contains("- Maren Holt\n"), "{oskar}");
+    }
+
+    #[test]
+    fn rolls_back_when_a_write_fails() {
+        let temp = workspace();
+        let root = temp.path();
+        let from = root.join("Characters/Elandra.character.md");
+        let to = root.join("Elandra Vosh.character.md");
+        let oskar = root.join("Characters/Oskar.character.md");
+        let original = fs::read_to_string(&oskar).expect("read");
+        // Writing over a folder fails after the move and the first write.
+        fs::create_dir_all(root.join("Blocked")).expect("create folder");
+
+        let result = apply(
+            &from,
+            &to,
+            &[
+                (oskar.clone(), original.clone(), "rewritten".to_string()),
+                (root.join("Blocked"), String::new(), "x".to_string()),
+            ],
+        );
+
+        assert!(matches!(result, Err(WorkspaceError::Io(_))));
+        assert!(from.exists());
+        assert!(!to.exists());
+        assert_eq!(fs::read_to_string(&oskar).expect("read"), original);
+    }
+
+    #[test]
+    fn refuses_existing_destination() {
+        let temp = workspace();
+
+        let error = rename_entity(
+            temp.path(),
+            "Characters/Elandra.character.md",
+            "Characters/Oskar.character.md",
+            false,
+        )
+        .expect_err("must refuse");
+
+        assert!(matches!(error, WorkspaceError::DestinationIsFile(_)));
+
+        let error = rename_entity(
+            temp.path(),
+            "Characters/Elandra.character.md",
+            "Characters",
+            false,
+        )
+        .expect_err("must refuse");
+
+        assert!(matches!(error, WorkspaceError::DestinationIsFolder(_)));
+        assert_eq!(
+            error.to_string(),
+            "A folder already exists at 'Characters'."
+        );
+        assert!(
+            temp.path()
+                .join("Characters/Elandra.character.md")
+                .is_file()
+        );
+    }
+}
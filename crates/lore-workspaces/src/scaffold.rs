@@ -0,0 +1,460 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use include_dir::{Dir, DirEntry, include_dir};
+use uuid::Uuid;
+
+use crate::{
+    manifest::WorkspaceManifest,
+    models::{CreateWorkspaceResult, WorkspaceVersion},
+    placeholders::TemplateVariables,
+    registry::{INTERNAL_DIR, SETTINGS_FILE, STATE_FILE, WorkspaceError, slugify_project_name},
+};
+
+pub(crate) const IGNORE_FILE: &str = ".loreignore";
+pub(crate) const TYPES_DIR: &str = "types";
+
+pub(crate) const DEFAULT_LOREIGNORE: &str = "\
+# Files Lore Designer skips while indexing. Uses .gitignore syntax.
+.DS_Store
+Thumbs.db
+*.tmp
+*~
+";
+
+const NOVEL_FOLDERS: &[&str] = &["Chapters", "Characters", "Places"];
+
+const NOVEL_FILES: &[(&str, &str)] = &[
+    (
+        "Index.md",
+        "\
+---
+type: index
+author: \"{{author}}\"
+created: \"{{created_at}}\"
+---
+
+# {{workspace_name}}
+
+- **Chapters/** holds the manuscript, one file per chapter.
+- **Characters/** holds a file for everyone who walks on stage.
+- **Places/** holds the locations your chapters visit.
+",
+    ),
+    (
+        "Chapters/Chapter 01.md",
+        "\
+---
+type: chapter
+title: \"Chapter 01\"
+order: 1
+status: draft
+pov: \"\"
+---
+
+# Chapter 01
+
+",
+    ),
+];
+
+const GAME_BIBLE_FOLDERS: &[&str] = &["Factions", "Regions", "Quests", "Characters", "Items"];
+
+/// Example entities, one per folder, each using its type's compound extension.
+const GAME_BIBLE_FILES: &[(&str, &str)] = &[
+    (
+        "Factions/Iron Court.faction.md",
+        "\
+---
+type: faction
+name: \"Iron Court\"
+leader: \"[[Warden Ilsa]]\"
+stance: hostile
+regions:
+  - \"[[Ashen Marches]]\"
+---
+
+# Iron Court
+
+Who they are, what they want, and what they will never give up.
+",
+    ),
+    (
+        "Regions/Ashen Marches.region.md",
+        "\
+---
+type: region
+name: \"Ashen Marches\"
+controlled_by: \"[[Iron Court]]\"
+danger: 3
+---
+
+# Ashen Marches
+
+Landmarks, travel routes, and what the player finds here.
+",
+    ),
+    (
+        "Quests/The Drowned Bell.quest.md",
+        "\
+---
+type: quest
+name: \"The Drowned Bell\"
+status: planned
+giver: \"[[Warden Ilsa]]\"
+region: \"[[Ashen Marches]]\"
+level: 1
+rewards:
+  - \"[[Tidecaller Lantern]]\"
+---
+
+# The Drowned Bell
+
+## Hook
+
+## Steps
+
+## Outcome
+",
+    ),
+    (
+        "Characters/Warden Ilsa.character.md",
+        "\
+---
+type: character
+name: \"Warden Ilsa\"
+faction: \"[[Iron Court]]\"
+status: active
+---
+
+# Warden Ilsa
+
+Motivation, voice, and the quests this character appears in.
+",
+    ),
+    (
+        "Items/Tidecaller Lantern.item.md",
+        "\
+---
+type: item
+name: \"Tidecaller Lantern\"
+rarity: rare
+owner: \"[[Warden Ilsa]]\"
+value: 250
+---
+
+# Tidecaller Lantern
+
+What it does, where it comes from, and who wants it.
+",
+    ),
+];
+
+/// Frontmatter schemas for the game-bible entity types, stored in `.lore/types/`.
+const GAME_BIBLE_TYPES: &[(&str, &str)] = &[
+    (
+        "faction.toml",
+        r#"id = "faction"
+display_name = "Faction"
+extension = "faction"
+folder = "Factions"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "leader"
+kind = "link"
+
+[[fields]]
+name = "stance"
+kind = "enum"
+options = ["allied", "neutral", "hostile"]
+
+[[fields]]
+name = "regions"
+kind = "list"
+items = "link"
+"#,
+    ),
+    (
+        "region.toml",
+        r#"id = "region"
+display_name = "Region"
+extension = "region"
+folder = "Regions"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "controlled_by"
+kind = "link"
+
+[[fields]]
+name = "danger"
+kind = "number"
+min = 0
+max = 5
+"#,
+    ),
+    (
+        "quest.toml",
+        r#"id = "quest"
+display_name = "Quest"
+extension = "quest"
+folder = "Quests"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "status"
+kind = "enum"
+options = ["planned", "active", "complete", "cut"]
+default = "planned"
+
+[[fields]]
+name = "giver"
+kind = "link"
+
+[[fields]]
+name = "region"
+kind = "link"
+
+[[fields]]
+name = "level"
+kind = "number"
+min = 1
+
+[[fields]]
+name = "rewards"
+kind = "list"
+items = "link"
+"#,
+    ),
+    (
+        "item.toml",
+        r#"id = "item"
+display_name = "Item"
+extension = "item"
+folder = "Items"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "rarity"
+kind = "enum"
+options = ["common", "uncommon", "rare", "legendary"]
+default = "common"
+
+[[fields]]
+name = "owner"
+kind = "link"
+
+[[fields]]
+name = "value"
+kind = "number"
+min = 0
+"#,
+    ),
+];
+
+/// The "Saltreach Cycle" worked example, embedded at build time.
+static SAMPLE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/sample");
+
+/// Everything a template needs to lay out a new workspace.
+pub(crate) struct NewWorkspace<'a> {
+    pub name: &'a str,
+    pub root_path: &'a Path,
+    pub app_version: &'a str,
+    pub author: Option<&'a str>,
+}
+
+impl NewWorkspace<'_> {
+    pub fn variables(&self, result: &CreateWorkspaceResult) -> TemplateVariables {
+        TemplateVariables {
+            workspace_name: self.name.to_string(),
+            created_at: result.created_at.clone(),
+            author: self.author.unwrap_or_default().to_string(),
+            slug: slugify_project_name(self.name),
+        }
+    }
+}
+
+pub(crate) fn create_blank_workspace(
+    workspace: &NewWorkspace<'_>,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let result = write_workspace_core(workspace, "blank")?;
+    fs::write(workspace.root_path.join(IGNORE_FILE), DEFAULT_LOREIGNORE)?;
+
+    Ok(result)
+}
+
+pub(crate) fn create_novel_workspace(
+    workspace: &NewWorkspace<'_>,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let result = write_workspace_core(workspace, "novel")?;
+    let variables = workspace.variables(&result);
+    let root_path = workspace.root_path;
+
+    for folder in NOVEL_FOLDERS {
+        fs::create_dir_all(root_path.join(folder))?;
+    }
+
+    fs::write(root_path.join(IGNORE_FILE), DEFAULT_LOREIGNORE)?;
+    write_files(root_path, NOVEL_FILES, &variables)?;
+
+    Ok(result)
+}
+
+pub(crate) fn create_game_bible_workspace(
+    workspace: &NewWorkspace<'_>,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let result = write_workspace_core(workspace, "game-bible")?;
+    let variables = workspace.variables(&result);
+    let root_path = workspace.root_path;
+
+    for folder in GAME_BIBLE_FOLDERS {
+        fs::create_dir_all(root_path.join(folder))?;
+    }
+
+    fs::write(root_path.join(IGNORE_FILE), DEFAULT_LOREIGNORE)?;
+    write_files(root_path, GAME_BIBLE_FILES, &variables)?;
+    write_files(
+        &root_path.join(INTERNAL_DIR).join(TYPES_DIR),
+        GAME_BIBLE_TYPES,
+        &variables,
+    )?;
+
+    Ok(result)
+}
+
+pub(crate) fn create_sample_workspace(
+    workspace: &NewWorkspace<'_>,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let result = write_workspace_core(workspace, "sample")?;
+    let variables = workspace.variables(&result);
+    write_embedded_dir(workspace.root_path, &SAMPLE_DIR, &variables)?;
+
+    Ok(result)
+}
+
+fn write_embedded_dir(
+    root_path: &Path,
+    dir: &Dir<'_>,
+    variables: &TemplateVariables,
+) -> Result<(), WorkspaceError> {
+    for entry in dir.entries() {
+        match entry {
+            DirEntry::Dir(child) => {
+                fs::create_dir_all(root_path.join(render_path(child.path(), variables)))?;
+                write_embedded_dir(root_path, child, variables)?;
+            }
+            DirEntry::File(file) => {
+                let path = root_path.join(render_path(file.path(), variables));
+                write_rendered(&path, file.contents(), variables)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_files(
+    root_path: &Path,
+    files: &[(&str, &str)],
+    variables: &TemplateVariables,
+) -> Result<(), WorkspaceError> {
+    for (relative_path, contents) in files {
+        let path = root_path.join(render_path(Path::new(relative_path), variables));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_rendered(&path, contents.as_bytes(), variables)?;
+    }
+
+    Ok(())
+}
+
+/// Renders every component of a template-relative path.
+pub(crate) fn render_path(relative_path: &Path, variables: &TemplateVariables) -> PathBuf {
+    relative_path
+        .components()
+        .map(|component| variables.render_file_name(&component.as_os_str().to_string_lossy()))
+        .collect()
+}
+
+/// Writes `contents` with placeholders filled in, escaped for the format of
+/// the file. Anything that isn't UTF-8, such as images, is copied byte for
+/// byte.
+pub(crate) fn write_rendered(
+    path: &Path,
+    contents: &[u8],
+    variables: &TemplateVariables,
+) -> io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    match std::str::from_utf8(contents) {
+        Ok(text) => fs::write(path, variables.render_file(&file_name, text)),
+        Err(_) => fs::write(path, contents),
+    }
+}
+
+/// Writes the manifest and `.lore/` shared by every template.
+pub(crate) fn write_workspace_core(
+    workspace: &NewWorkspace<'_>,
+    template_id: &str,
+) -> Result<CreateWorkspaceResult, WorkspaceError> {
+    let NewWorkspace {
+        name,
+        root_path,
+        app_version,
+        ..
+    } = *workspace;
+
+    fs::create_dir_all(root_path)?;
+
+    let internal_dir = root_path.join(INTERNAL_DIR);
+    fs::create_dir_all(&internal_dir)?;
+
+    let manifest_name = format!("{}.lore", slugify_project_name(name));
+    let manifest_path = root_path.join(manifest_name);
+    let created_at = Utc::now();
+    let id = Uuid::new_v4();
+
+    let manifest = WorkspaceManifest {
+        id: Some(id),
+        name: name.to_string(),
+        workspace_version: WorkspaceVersion::default().major,
+        created_with: app_version.to_string(),
+        created_at: created_at.to_rfc3339(),
+        template_id: template_id.to_string(),
+        extra: toml::Table::new(),
+    };
+
+    manifest.write(&manifest_path)?;
+    fs::write(internal_dir.join(SETTINGS_FILE), "")?;
+    fs::write(internal_dir.join(STATE_FILE), "{}\n")?;
+
+    Ok(CreateWorkspaceResult {
+        id: id.to_string(),
+        name: name.to_string(),
+        root_path: root_path.display().to_string(),
+        manifest_path: manifest_path.display().to_string(),
+        created_at: created_at.to_rfc3339(),
+        template_id: template_id.to_string(),
+        workspace_version: WorkspaceVersion::default(),
+    })
+}
@@ -0,0 +1,352 @@
+use std::{cmp::Ordering, fs, io, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{ignore::WorkspaceFilter, registry::WorkspaceError};
+
+const MARKDOWN_EXTENSION: &str = "md";
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "avif"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryKind {
+    Folder,
+    /// `*.character.md`
+    Character,
+    /// `*.location.md`
+    Location,
+    /// Any other `*.<type>.md`, such as `*.faction.md`.
+    Entity,
+    /// Plain `*.md`.
+    Document,
+    Image,
+    Other,
+}
+
+impl EntryKind {
+    pub fn is_markdown(self) -> bool {
+        matches!(
+            self,
+            Self::Character | Self::Location | Self::Entity | Self::Document
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEntry {
+    pub name: String,
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub kind: EntryKind,
+    /// The middle part of a compound extension, e.g. `character`.
+    pub entity_type: Option<String>,
+    /// File size in bytes; for folders, the total of everything listed inside.
+    pub size: u64,
+    pub modified_at: Option<DateTime<Utc>>,
+    /// Present for folders only. Folders come first, then files, by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<WorkspaceEntry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTree {
+    pub root_path: String,
+    pub entries: Vec<WorkspaceEntry>,
+}
+
+impl WorkspaceTree {
+    /// Every file in the tree, depth first.
+    pub(crate) fn files(&self) -> Vec<&WorkspaceEntry> {
+        fn collect<'a>(entries: &'a [WorkspaceEntry], files: &mut Vec<&'a WorkspaceEntry>) {
+            for entry in entries {
+                match &entry.children {
+                    Some(children) => collect(children, files),
+                    None if entry.kind != EntryKind::Folder => files.push(entry),
+                    None => {}
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        collect(&self.entries, &mut files);
+        files
+    }
+}
+
+/// Lists the workspace content under `root`, skipping `.lore/` and anything
+/// matched by `.loreignore`. Symlinked folders are listed but not followed.
+pub fn scan_workspace(root: impl AsRef<Path>) -> Result<WorkspaceTree, WorkspaceError> {
+    let root = root.as_ref();
+    if !root.is_dir() {
+        return Err(WorkspaceError::WorkspaceNotFound(
+            root.display().to_string(),
+        ));
+    }
+
+    let filter = WorkspaceFilter::load(root)?;
+    let entries = scan_dir(root, &filter, root)?;
+
+    Ok(WorkspaceTree {
+        root_path: root.display().to_string(),
+        entries,
+    })
+}
+
+/// Classifies a file by its (compound) extension, returning the entity type
+/// for `*.<type>.md` files.
+pub(crate) fn classify_file(file_name: &str) -> (EntryKind, Option<String>) {
+    let lower = file_name.to_lowercase();
+    let Some((stem, extension)) = lower.rsplit_once('.') else {
+        return (EntryKind::Other, None);
+    };
+
+    if extension == MARKDOWN_EXTENSION {
+        return match entity_type(stem) {
+            Some(entity_type) => {
+                let kind = match entity_type.as_str() {
+                    "character" => EntryKind::Character,
+                    "location" => EntryKind::Location,
+                    _ => EntryKind::Entity,
+                };
+                (kind, Some(entity_type))
+            }
+            None => (EntryKind::Document, None),
+        };
+    }
+
+    if IMAGE_EXTENSIONS.contains(&extension) {
+        return (EntryKind::Image, None);
+    }
+
+    (EntryKind::Other, None)
+}
+
+/// A Markdown file's name without its (compound) extension, e.g. `Maren`
+/// for `Maren.character.md`.
+pub(crate) fn entity_name(file_name: &str) -> &str {
+    let Some(stem) = strip_suffix_ignore_case(file_name, ".md") else {
+        return file_name;
+    };
+
+    match classify_file(file_name).1 {
+        Some(entity_type) => {
+            strip_suffix_ignore_case(stem, &format!(".{entity_type}")).unwrap_or(stem)
+        }
+        None => stem,
+    }
+}
+
+fn strip_suffix_ignore_case<'a>(text: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = text.len().checked_sub(suffix.len())?;
+    (text.is_char_boundary(split) && text[split..].eq_ignore_ascii_case(suffix))
+        .then(|| &text[..split])
+}
+
+fn entity_type(stem: &str) -> Option<String> {
+    let (name, entity_type) = stem.rsplit_once('.')?;
+    let valid = !name.is_empty()
+        && entity_type.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && entity_type
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+
+    valid.then(|| entity_type.to_string())
+}
+
+fn scan_dir(
+    dir: &Path,
+    filter: &WorkspaceFilter,
+    root: &Path,
+) -> Result<Vec<WorkspaceEntry>, WorkspaceError> {
+    let mut entries = Vec::new();
+
+    for dir_entry in fs::read_dir(dir)? {
+        if let Some(entry) = read_entry(root, &dir_entry?.path(), filter)? {
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by(compare_entries);
+    Ok(entries)
+}
+
+/// Reads a single path (and, for folders, everything below it). Returns
+/// `None` when the path is gone, ignored, or a dangling symlink.
+pub(crate) fn read_entry(
+    root: &Path,
+    path: &Path,
+    filter: &WorkspaceFilter,
+) -> Result<Option<WorkspaceEntry>, WorkspaceError> {
+    let link_metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let is_symlink = link_metadata.file_type().is_symlink();
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        // Dangling symlinks have nothing to show.
+        Err(_) if is_symlink => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let is_dir = metadata.is_dir();
+    let relative_path = relative_path(root, path);
+
+    if filter.is_ignored_entry(Path::new(&relative_path), is_dir) {
+        return Ok(None);
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let modified_at = metadata.modified().ok().map(DateTime::<Utc>::from);
+
+    let entry = if is_dir {
+        let children = if is_symlink {
+            Vec::new()
+        } else {
+            scan_dir(path, filter, root)?
+        };
+
+        WorkspaceEntry {
+            name,
+            path: relative_path,
+            kind: EntryKind::Folder,
+            entity_type: None,
+            size: children.iter().map(|child| child.size).sum(),
+            modified_at,
+            children: Some(children),
+        }
+    } else {
+        let (kind, entity_type) = classify_file(&name);
+
+        WorkspaceEntry {
+            name,
+            path: relative_path,
+            kind,
+            entity_type,
+            size: metadata.len(),
+            modified_at,
+            children: None,
+        }
+    };
+
+    Ok(Some(entry))
+}
+
+pub(crate) fn relative_path(root: &Path, path: &Path) -> String {
+    to_slash_path(path.strip_prefix(root).unwrap_or(path))
+}
+
+pub(crate) fn to_slash_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn compare_entries(left: &WorkspaceEntry, right: &WorkspaceEntry) -> Ordering {
+    let left_is_folder = left.kind == EntryKind::Folder;
+    let right_is_folder = right.kind == EntryKind::Folder;
+
+    right_is_folder
+        .cmp(&left_is_folder)
+        .then_with(|| left.name.to_lowercase().cmp(&right.name.to_lowercase()))
+        .then_with(|| left.name.cmp(&right.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{EntryKind, classify_file, entity_name, scan_workspace};
+
+    #[test]
+    fn classifies_compound_extensions() {
+        assert_eq!(
+            classify_file("Maren Holt.character.md"),
+            (EntryKind::Character, Some("character".to_string()))
+        );
+        assert_eq!(
+            classify_file("Saltreach.Location.md"),
+            (EntryKind::Location, Some("location".to_string()))
+        );
+        assert_eq!(
+            classify_file("Iron Court.faction.md"),
+            (EntryKind::Entity, Some("faction".to_string()))
+        );
+        assert_eq!(classify_file("Chapter 01.md"), (EntryKind::Document, None));
+        assert_eq!(classify_file("v1.2 notes.md"), (EntryKind::Document, None));
+        assert_eq!(classify_file("map.PNG"), (EntryKind::Image, None));
+        assert_eq!(classify_file("atlas.lore"), (EntryKind::Other, None));
+
+        assert_eq!(entity_name("Maren Holt.character.md"), "Maren Holt");
+        assert_eq!(entity_name("Saltreach.Location.MD"), "Saltreach");
+        assert_eq!(entity_name("v1.2 notes.md"), "v1.2 notes");
+    }
+
+    #[test]
+    fn builds_sorted_tree_with_sizes() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters")).expect("create folder");
+        fs::write(root.join("Characters/Maren.character.md"), "12345").expect("write");
+        fs::write(root.join("Characters/portrait.png"), "123").expect("write");
+        fs::write(root.join("Start Here.md"), "1").expect("write");
+        fs::write(root.join("appendix.md"), "1").expect("write");
+
+        let tree = scan_workspace(root).expect("scanned");
+
+        let names: Vec<_> = tree
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["Characters", "appendix.md", "Start Here.md"]);
+
+        let characters = &tree.entries[0];
+        assert_eq!(characters.kind, EntryKind::Folder);
+        assert_eq!(characters.size, 8);
+        let children = characters.children.as_ref().expect("folder children");
+        assert_eq!(children[0].path, "Characters/Maren.character.md");
+        assert_eq!(children[0].kind, EntryKind::Character);
+        assert_eq!(children[1].kind, EntryKind::Image);
+        assert!(children[0].modified_at.is_some());
+        assert!(children[0].children.is_none());
+    }
+
+    #[test]
+    fn skips_internal_dir_and_ignored_paths() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join(".lore/cache")).expect("create internal dir");
+        fs::create_dir_all(root.join("Exports")).expect("create folder");
+        fs::write(root.join(".loreignore"), "Exports/\n*.tmp\n").expect("write ignore");
+        fs::write(root.join("notes.tmp"), "").expect("write");
+        fs::write(root.join("Exports/book.md"), "").expect("write");
+        fs::write(root.join("Lore.md"), "").expect("write");
+
+        let tree = scan_workspace(root).expect("scanned");
+
+        let names: Vec<_> = tree
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, [".loreignore", "Lore.md"]);
+    }
+
+    #[test]
+    fn reports_missing_root() {
+        let temp = tempdir().expect("tempdir");
+
+        assert!(scan_workspace(temp.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,470 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use lore_core::{Document, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::resolve_workspace_path,
+    registry::{INTERNAL_DIR, WorkspaceError},
+    scaffold::TYPES_DIR,
+    scan::classify_file,
+};
+
+/// Frontmatter key naming a document's entity type.
+pub(crate) const TYPE_KEY: &str = "type";
+
+const TYPE_FILE_EXTENSION: &str = "toml";
+
+const BUILTIN_TYPES: &[&str] = &[
+    r#"id = "character"
+display_name = "Character"
+extension = "character"
+folder = "Characters"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "aliases"
+kind = "list"
+items = "string"
+
+[[fields]]
+name = "archetype"
+kind = "string"
+
+[[fields]]
+name = "status"
+kind = "enum"
+options = ["active", "inactive", "deceased", "unknown"]
+default = "active"
+
+[[fields]]
+name = "home"
+kind = "link"
+target = "location"
+
+[[fields]]
+name = "allies"
+kind = "list"
+items = "link"
+target = "character"
+"#,
+    r#"id = "location"
+display_name = "Location"
+extension = "location"
+folder = "Places"
+
+[[fields]]
+name = "name"
+kind = "string"
+required = true
+
+[[fields]]
+name = "aliases"
+kind = "list"
+items = "string"
+
+[[fields]]
+name = "region"
+kind = "string"
+
+[[fields]]
+name = "population"
+kind = "number"
+min = 0
+
+[[fields]]
+name = "notable_residents"
+kind = "list"
+items = "link"
+target = "character"
+"#,
+];
+
+/// An entity type, read from `.lore/types/<id>.toml` or built in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "snake_case"))]
+pub struct EntitySchema {
+    pub id: String,
+    pub display_name: String,
+    /// Middle part of the compound extension, e.g. `character` for
+    /// `Hero.character.md`. Defaults to the id.
+    #[serde(default)]
+    pub extension: String,
+    /// Folder new entities of this type are created in.
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<FieldSchema>,
+    #[serde(default, skip_deserializing)]
+    pub is_builtin: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "snake_case"))]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub kind: FieldKind,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values of an `enum` field, or of an `enum` list's items.
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub default: Option<Value>,
+    /// Bounds on a number's value, or on a string's or list's length. For a
+    /// list they bound the number of items, not the items themselves.
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Kind of each item in a `list` field.
+    #[serde(default)]
+    pub items: Option<FieldKind>,
+    /// Entity type a `link` (or list of links) must point to.
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    String,
+    Enum,
+    Number,
+    /// An ISO 8601 date such as `2025-03-01`.
+    Date,
+    /// A wiki-link such as `"[[Saltreach]]"`.
+    Link,
+    List,
+}
+
+impl EntitySchema {
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("`id` cannot be empty".to_string());
+        }
+
+        for (index, field) in self.fields.iter().enumerate() {
+            if self.fields[..index]
+                .iter()
+                .any(|other| other.name == field.name)
+            {
+                return Err(format!("field `{}` is declared twice", field.name));
+            }
+            field
+                .check()
+                .map_err(|reason| format!("field `{}`: {reason}", field.name))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FieldSchema {
+    /// Kind that `options` and `target` apply to: the item kind for lists.
+    pub fn value_kind(&self) -> FieldKind {
+        match self.kind {
+            FieldKind::List => self.items.unwrap_or(FieldKind::String),
+            kind => kind,
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.items == Some(FieldKind::List) {
+            return Err("lists of lists are not supported".to_string());
+        }
+        if self.value_kind() == FieldKind::Enum && self.options.is_empty() {
+            return Err("an enum needs `options`".to_string());
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max)
+            && min > max
+        {
+            return Err("`min` is greater than `max`".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// The entity types of a workspace: built-ins, overridden or extended by the
+/// files in `.lore/types/`.
+#[derive(Debug, Default)]
+pub(crate) struct EntityTypes {
+    pub types: Vec<EntitySchema>,
+    /// Type files that could not be used, with the reason.
+    pub errors: Vec<WorkspaceError>,
+}
+
+impl EntityTypes {
+    pub fn load(root: &Path) -> Result<Self, WorkspaceError> {
+        let mut types = Self::default();
+        for source in BUILTIN_TYPES {
+            let mut schema: EntitySchema =
+                toml::from_str(source).expect("built-in entity types are valid");
+            schema.is_builtin = true;
+            types.insert(normalized(schema));
+        }
+
+        let types_dir = root.join(INTERNAL_DIR).join(TYPES_DIR);
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&types_dir) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == TYPE_FILE_EXTENSION)
+                })
+                .collect(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error.into()),
+        };
+        paths.sort();
+
+        for path in paths {
+            match read_type_file(&path) {
+                Ok(schema) => types.insert(schema),
+                Err(error) => types.errors.push(error),
+            }
+        }
+
+        Ok(types)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&EntitySchema> {
+        self.types.iter().find(|schema| schema.id == id)
+    }
+
+    pub fn for_extension(&self, extension: &str) -> Option<&EntitySchema> {
+        self.types
+            .iter()
+            .find(|schema| schema.extension.eq_ignore_ascii_case(extension))
+    }
+
+    /// The type a document declares with `type:`, falling back to its
+    /// compound extension.
+    pub fn for_document(
+        &self,
+        file_name: &str,
+        document: Option<&Document>,
+    ) -> Option<&EntitySchema> {
+        let declared = document
+            .and_then(|document| document.get(TYPE_KEY))
+            .and_then(Value::as_str);
+
+        match declared {
+            Some(id) => self.get(id),
+            None => classify_file(file_name)
+                .1
+                .and_then(|extension| self.for_extension(&extension)),
+        }
+    }
+
+    /// Workspace files replace built-ins with the same id.
+    fn insert(&mut self, schema: EntitySchema) {
+        match self
+            .types
+            .iter_mut()
+            .find(|existing| existing.id == schema.id)
+        {
+            Some(existing) => *existing = schema,
+            None => self.types.push(schema),
+        }
+    }
+}
+
+fn read_type_file(path: &Path) -> Result<EntitySchema, WorkspaceError> {
+    let malformed = |reason: String| WorkspaceError::MalformedEntityType {
+        path: path.display().to_string(),
+        reason,
+    };
+
+    let contents = fs::read_to_string(path)?;
+    let schema: EntitySchema =
+        toml::from_str(&contents).map_err(|error| malformed(error.message().to_string()))?;
+    schema.check().map_err(malformed)?;
+
+    Ok(normalized(schema))
+}
+
+fn normalized(mut schema: EntitySchema) -> EntitySchema {
+    if schema.extension.trim().is_empty() {
+        schema.extension = schema.id.clone();
+    }
+    schema
+}
+
+/// Every entity type available in the workspace, ordered by display name.
+/// Malformed type files are skipped.
+pub fn list_entity_types(root: impl AsRef<Path>) -> Result<Vec<EntitySchema>, WorkspaceError> {
+    let mut types = EntityTypes::load(root.as_ref())?.types;
+    types.sort_by(|left, right| left.display_name.cmp(&right.display_name));
+    Ok(types)
+}
+
+/// The schema of the entity type `path` declares, if any. A document whose
+/// frontmatter cannot be parsed falls back to its compound extension.
+pub fn entity_schema_for_file(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<Option<EntitySchema>, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+    let types = EntityTypes::load(&root)?;
+
+    let document = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| Document::parse(&content).ok());
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(types.for_document(&file_name, document.as_ref()).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{EntityTypes, FieldKind, entity_schema_for_file, list_entity_types};
+    use crate::registry::WorkspaceError;
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore/types")).expect("create types dir");
+        temp
+    }
+
+    #[test]
+    fn provides_builtin_character_and_location_types() {
+        let temp = workspace();
+
+        let types = list_entity_types(temp.path()).expect("types");
+
+        let ids: Vec<_> = types.iter().map(|schema| schema.id.as_str()).collect();
+        assert_eq!(ids, ["character", "location"]);
+        let character = &types[0];
+        assert!(character.is_builtin);
+        let home = character.field("home").expect("home field");
+        assert_eq!(home.kind, FieldKind::Link);
+        assert_eq!(home.target.as_deref(), Some("location"));
+        let status = character.field("status").expect("status field");
+        assert_eq!(
+            status.default.as_ref().and_then(|value| value.as_str()),
+            Some("active")
+        );
+    }
+
+    #[test]
+    fn loads_workspace_types_and_overrides_builtins() {
+        let temp = workspace();
+        let types_dir = temp.path().join(".lore/types");
+        fs::write(
+            types_dir.join("faction.toml"),
+            "id = \"faction\"\ndisplay_name = \"Faction\"\n\n[[fields]]\nname = \"founded\"\nkind = \"date\"\n",
+        )
+        .expect("write faction");
+        fs::write(
+            types_dir.join("character.toml"),
+            "id = \"character\"\ndisplay_name = \"Hero\"\n",
+        )
+        .expect("write override");
+
+        let types = EntityTypes::load(temp.path()).expect("types");
+
+        let faction = types.get("faction").expect("faction type");
+        assert_eq!(faction.extension, "faction");
+        assert_eq!(faction.fields[0].kind, FieldKind::Date);
+        let character = types.get("character").expect("character type");
+        assert_eq!(character.display_name, "Hero");
+        assert!(!character.is_builtin);
+        assert!(types.errors.is_empty());
+    }
+
+    #[test]
+    fn reports_malformed_type_files() {
+        let temp = workspace();
+        let types_dir = temp.path().join(".lore/types");
+        fs::write(types_dir.join("broken.toml"), "id = ").expect("write broken");
+        fs::write(
+            types_dir.join("mood.toml"),
+            "id = \"mood\"\ndisplay_name = \"Mood\"\n\n[[fields]]\nname = \"tone\"\nkind = \"enum\"\n",
+        )
+        .expect("write enum without options");
+
+        let types = EntityTypes::load(temp.path()).expect("types");
+
+        assert!(types.get("mood").is_none());
+        assert_eq!(types.errors.len(), 2);
+        assert!(
+            types
+                .errors
+                .iter()
+                .all(|error| matches!(error, WorkspaceError::MalformedEntityType { .. }))
+        );
+    }
+
+    #[test]
+    fn resolves_schema_from_frontmatter_or_extension() {
+        let temp = workspace();
+        let root = temp.path();
+        fs::write(root.join("Maren.character.md"), "# Maren\n").expect("write");
+        fs::write(root.join("Saltreach.md"), "---\ntype: location\n---\n").expect("write");
+        fs::write(root.join("Notes.md"), "Just notes\n").expect("write");
+
+        let by_extension = entity_schema_for_file(root, "Maren.character.md").expect("schema");
+        let by_type = entity_schema_for_file(root, "Saltreach.md").expect("schema");
+        let untyped = entity_schema_for_file(root, "Notes.md").expect("schema");
+
+        assert_eq!(
+            by_extension.map(|schema| schema.id).as_deref(),
+            Some("character")
+        );
+        assert_eq!(by_type.map(|schema| schema.id).as_deref(), Some("location"));
+        assert!(untyped.is_none());
+    }
+
+    #[test]
+    fn game_bible_types_are_valid_schemas() {
+        let temp = workspace();
+        let parent = temp.path().join("Lore");
+        let created = crate::create_workspace(crate::CreateWorkspaceRequest {
+            name: "Iron Bible".to_string(),
+            parent_path: parent.display().to_string(),
+            template_id: "game-bible".to_string(),
+            app_version: "0.1.0".to_string(),
+            author: None,
+        })
+        .expect("workspace created");
+
+        let types = EntityTypes::load(created.root_path.as_ref()).expect("types");
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        for id in [
+            "faction",
+            "region",
+            "quest",
+            "item",
+            "character",
+            "location",
+        ] {
+            assert!(types.get(id).is_some(), "missing {id}");
+        }
+    }
+}
@@ -0,0 +1,768 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use lore_core::{Document, Value};
+use serde::{Deserialize, Serialize};
+use tantivy::{
+    Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term,
+    collector::{DocSetCollector, TopDocs},
+    query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, TermQuery},
+    schema::{
+        Facet, FacetOptions, Field, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing,
+        TextOptions, Value as _,
+    },
+    snippet::SnippetGenerator,
+    tokenizer::{AsciiFoldingFilter, LowerCaser, RemoveLongFilter, SimpleTokenizer, TextAnalyzer},
+};
+
+use crate::{
+    index::{document_title, entity_type_of},
+    registry::{CACHE_DIR, INTERNAL_DIR, WorkspaceError},
+    scan::{classify_file, scan_workspace},
+    schema::EntityTypes,
+    watcher::{WorkspaceChange, WorkspaceChanged},
+};
+
+/// The index lives in `.lore/cache/search/` and can always be rebuilt from
+/// the workspace files.
+const SEARCH_DIR: &str = "search";
+/// Bump when the schema or tokenizer changes; older indexes are rebuilt.
+const SEARCH_FORMAT_VERSION: u32 = 2;
+const FORMAT_FILE: &str = "format";
+
+const TOKENIZER: &str = "lore";
+const WRITER_MEMORY_BYTES: usize = 20_000_000;
+const TITLE_BOOST: f32 = 3.0;
+const FRONTMATTER_BOOST: f32 = 1.5;
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Larger limits are lowered to this; tantivy allocates by the limit.
+pub const MAX_SEARCH_LIMIT: usize = 1_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    /// Only documents of these entity types; any type when empty.
+    #[serde(default)]
+    pub entity_types: Vec<String>,
+    /// Only documents in this folder or below it.
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub title: String,
+    pub entity_type: Option<String>,
+    pub score: f32,
+    /// An excerpt of the body around the best matches.
+    pub snippet: String,
+    /// Ranges of `snippet` that matched the query, in UTF-16 code units as
+    /// JavaScript indexes strings.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    path: Field,
+    title: Field,
+    body: Field,
+    frontmatter: Field,
+    entity_type: Field,
+    folder: Field,
+    /// Size and modification time of the file when it was indexed.
+    stamp: Field,
+}
+
+impl Fields {
+    fn schema() -> (Schema, Self) {
+        let text = |stored: bool| {
+            let options = TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            );
+            if stored {
+                options.set_stored()
+            } else {
+                options
+            }
+        };
+
+        let mut builder = Schema::builder();
+        let fields = Self {
+            path: builder.add_text_field("path", STRING | STORED),
+            title: builder.add_text_field("title", text(true)),
+            body: builder.add_text_field("body", text(true)),
+            frontmatter: builder.add_text_field("frontmatter", text(false)),
+            entity_type: builder.add_text_field("entity_type", STRING | STORED),
+            folder: builder.add_facet_field("folder", FacetOptions::default()),
+            stamp: builder.add_text_field("stamp", STRING | STORED),
+        };
+
+        (builder.build(), fields)
+    }
+}
+
+/// Full-text index over the Markdown files of a workspace. It is a cache:
+/// anything wrong with it is fixed by [`SearchIndex::rebuild`].
+pub struct SearchIndex {
+    root: PathBuf,
+    index: Index,
+    reader: IndexReader,
+    fields: Fields,
+    /// Serializes writers; tantivy allows one at a time.
+    writing: Mutex<()>,
+    needs_rebuild: bool,
+}
+
+impl SearchIndex {
+    /// Opens the index of the workspace at `root`, creating an empty one
+    /// when it is missing, outdated or unreadable. Check
+    /// [`needs_rebuild`](Self::needs_rebuild) afterwards.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, WorkspaceError> {
+        let root = root.as_ref();
+        let root = root
+            .canonicalize()
+            .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+        let dir = root.join(INTERNAL_DIR).join(CACHE_DIR).join(SEARCH_DIR);
+        let (schema, fields) = Fields::schema();
+
+        let existing = (read_format(&dir) == Some(SEARCH_FORMAT_VERSION))
+            .then(|| Index::open_in_dir(&dir).ok())
+            .flatten()
+            .filter(|index| index.schema() == schema);
+
+        let (index, needs_rebuild) = match existing {
+            Some(index) => (index, false),
+            None => {
+                match fs::remove_dir_all(&dir) {
+                    Ok(()) => {}
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                    Err(error) => return Err(error.into()),
+                }
+                fs::create_dir_all(&dir)?;
+                let index = Index::create_in_dir(&dir, schema)?;
+                fs::write(dir.join(FORMAT_FILE), SEARCH_FORMAT_VERSION.to_string())?;
+                (index, true)
+            }
+        };
+
+        index.tokenizers().register(
+            TOKENIZER,
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(RemoveLongFilter::limit(40))
+                .filter(LowerCaser)
+                .filter(AsciiFoldingFilter)
+                .build(),
+        );
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+
+        Ok(Self {
+            root,
+            index,
+            reader,
+            fields,
+            writing: Mutex::new(()),
+            needs_rebuild,
+        })
+    }
+
+    /// Whether the index was just created and is still empty.
+    pub fn needs_rebuild(&self) -> bool {
+        self.needs_rebuild
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Re-indexes every file from scratch. Returns the number of documents.
+    pub fn rebuild(&self) -> Result<usize, WorkspaceError> {
+        let types = EntityTypes::load(&self.root)?;
+        let tree = scan_workspace(&self.root)?;
+
+        let count = self.write(|writer| {
+            writer.delete_all_documents()?;
+            let mut count = 0;
+            for entry in tree.files() {
+                if entry.kind.is_markdown() && self.add_file(writer, &types, &entry.path)? {
+                    count += 1;
+                }
+            }
+            Ok(count)
+        })?;
+
+        Ok(count)
+    }
+
+    /// Re-indexes the files whose size or modification time changed since
+    /// they were indexed, and drops the ones that are gone, catching up on
+    /// edits made while nothing was watching. Returns how many documents
+    /// changed.
+    pub fn reconcile(&self) -> Result<usize, WorkspaceError> {
+        let types = EntityTypes::load(&self.root)?;
+        let tree = scan_workspace(&self.root)?;
+
+        let mut indexed = self.indexed_stamps()?;
+        let mut stale = Vec::new();
+        for entry in tree.files() {
+            if !entry.kind.is_markdown() {
+                continue;
+            }
+            let current = file_stamp(entry.size, entry.modified_at);
+            match indexed.remove(&entry.path) {
+                Some(Some(stamp)) if Some(&stamp) == current.as_ref() => {}
+                _ => stale.push(entry.path.clone()),
+            }
+        }
+        let changed = stale.len() + indexed.len();
+        if changed == 0 {
+            return Ok(0);
+        }
+
+        self.write(|writer| {
+            for path in indexed.keys().chain(&stale) {
+                writer.delete_term(Term::from_field_text(self.fields.path, path));
+            }
+            for path in &stale {
+                self.add_file(writer, &types, path)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(changed)
+    }
+
+    /// Brings the index up to date with a batch of watcher events.
+    pub fn apply(&self, changed: &WorkspaceChanged) -> Result<(), WorkspaceError> {
+        if changed.rescan {
+            return self.rebuild().map(|_| ());
+        }
+
+        let types = EntityTypes::load(&self.root)?;
+        self.write(|writer| {
+            for change in &changed.changes {
+                match change {
+                    WorkspaceChange::Created { entry } | WorkspaceChange::Modified { entry } => {
+                        self.remove_path(writer, &entry.path);
+                        self.add_path(writer, &types, &entry.path)?;
+                    }
+                    WorkspaceChange::Removed { path } => self.remove_path(writer, path),
+                    WorkspaceChange::Renamed { from, to, .. } => {
+                        self.remove_path(writer, from);
+                        self.remove_path(writer, to);
+                        self.add_path(writer, &types, to)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Ranked documents matching `query`, which accepts tantivy's query
+    /// syntax (`"exact phrase"`, `-excluded`, `title:maren`) but never fails
+    /// on malformed input. Every word must match. `limit` is clamped to
+    /// `1..=MAX_SEARCH_LIMIT`.
+    pub fn search(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>, WorkspaceError> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let fields = self.fields;
+
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![fields.title, fields.body, fields.frontmatter],
+        );
+        parser.set_conjunction_by_default();
+        parser.set_field_boost(fields.title, TITLE_BOOST);
+        parser.set_field_boost(fields.frontmatter, FRONTMATTER_BOOST);
+        let (text_query, _) = parser.parse_query_lenient(query);
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        if !filters.entity_types.is_empty() {
+            let types = filters
+                .entity_types
+                .iter()
+                .map(|entity_type| {
+                    let term = Term::from_field_text(fields.entity_type, entity_type);
+                    let query: Box<dyn Query> =
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic));
+                    (Occur::Should, query)
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(types))));
+        }
+        if let Some(folder) = filters.folder.as_deref().and_then(folder_facet) {
+            let term = Term::from_facet(fields.folder, &folder);
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+
+        let searcher = self.reader.searcher();
+        let snippets = SnippetGenerator::create(&searcher, &*text_query, fields.body)?;
+        clauses.push((Occur::Must, text_query));
+        let query = BooleanQuery::new(clauses);
+        let top = searcher.search(
+            &query,
+            &TopDocs::with_limit(limit.clamp(1, MAX_SEARCH_LIMIT)).order_by_score(),
+        )?;
+
+        let mut hits = Vec::with_capacity(top.len());
+        for (score, address) in top {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let text = |field| {
+                document
+                    .get_first(field)
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string)
+            };
+            let snippet = snippets.snippet_from_doc(&document);
+            let fragment = snippet.fragment();
+            let utf16 = |byte: usize| fragment[..byte].encode_utf16().count();
+
+            hits.push(SearchHit {
+                path: text(fields.path).unwrap_or_default(),
+                title: text(fields.title).unwrap_or_default(),
+                entity_type: text(fields.entity_type),
+                score,
+                highlights: snippet
+                    .highlighted()
+                    .iter()
+                    .map(|range| (utf16(range.start), utf16(range.end)))
+                    .collect(),
+                snippet: fragment.to_string(),
+            });
+        }
+
+        Ok(hits)
+    }
+
+    /// The stamp of every indexed document, by path.
+    fn indexed_stamps(&self) -> Result<HashMap<String, Option<String>>, WorkspaceError> {
+        let searcher = self.reader.searcher();
+        let mut stamps = HashMap::new();
+        for address in searcher.search(&AllQuery, &DocSetCollector)? {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let text = |field| {
+                document
+                    .get_first(field)
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string)
+            };
+            if let Some(path) = text(self.fields.path) {
+                stamps.insert(path, text(self.fields.stamp));
+            }
+        }
+        Ok(stamps)
+    }
+
+    fn write<T>(
+        &self,
+        f: impl FnOnce(&mut IndexWriter) -> Result<T, WorkspaceError>,
+    ) -> Result<T, WorkspaceError> {
+        let _guard = self.writing.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut writer = self.index.writer_with_num_threads(1, WRITER_MEMORY_BYTES)?;
+
+        match f(&mut writer) {
+            Ok(value) => {
+                writer.commit()?;
+                self.reader.reload()?;
+                Ok(value)
+            }
+            Err(error) => {
+                let _ = writer.rollback();
+                Err(error)
+            }
+        }
+    }
+
+    /// Indexes `path`, or everything below it when it is a folder.
+    fn add_path(
+        &self,
+        writer: &IndexWriter,
+        types: &EntityTypes,
+        path: &str,
+    ) -> Result<(), WorkspaceError> {
+        let absolute = self.root.join(path);
+        if absolute.is_dir() {
+            let tree = scan_workspace(&self.root)?;
+            let prefix = format!("{path}/");
+            for entry in tree.files() {
+                if entry.kind.is_markdown() && entry.path.starts_with(&prefix) {
+                    self.add_file(writer, types, &entry.path)?;
+                }
+            }
+        } else if classify_file(path.rsplit('/').next().unwrap_or(path))
+            .0
+            .is_markdown()
+        {
+            self.add_file(writer, types, path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Drops `path`, and everything below it should it be a folder.
+    fn remove_path(&self, writer: &IndexWriter, path: &str) {
+        writer.delete_term(Term::from_field_text(self.fields.path, path));
+        if let Some(folder) = folder_facet(path) {
+            writer.delete_term(Term::from_facet(self.fields.folder, &folder));
+        }
+    }
+
+    /// Returns `false` when the file could not be read.
+    fn add_file(
+        &self,
+        writer: &IndexWriter,
+        types: &EntityTypes,
+        path: &str,
+    ) -> Result<bool, WorkspaceError> {
+        let absolute = self.root.join(path);
+        // Stamped before reading, so an edit in between is caught next time.
+        let Ok(metadata) = fs::metadata(&absolute) else {
+            return Ok(false);
+        };
+        let Ok(content) = fs::read_to_string(&absolute) else {
+            return Ok(false);
+        };
+        let fields = self.fields;
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let parsed = Document::parse(&content).ok();
+
+        let title = document_title(file_name, parsed.as_ref()).to_string();
+        let body = parsed
+            .as_ref()
+            .map_or(content.as_str(), |document| document.body());
+        let mut frontmatter = String::new();
+        if let Some(document) = &parsed {
+            for value in document.frontmatter().values() {
+                flatten_text(value, &mut frontmatter);
+            }
+        }
+
+        let mut document = TantivyDocument::default();
+        document.add_text(fields.path, path);
+        document.add_text(fields.title, &title);
+        document.add_text(fields.body, body);
+        document.add_text(fields.frontmatter, &frontmatter);
+        if let Some(entity_type) = entity_type_of(types, file_name, parsed.as_ref()) {
+            document.add_text(fields.entity_type, &entity_type);
+        }
+        let folder = path
+            .rsplit_once('/')
+            .and_then(|(folder, _)| folder_facet(folder))
+            .unwrap_or_else(Facet::root);
+        document.add_facet(fields.folder, folder);
+        let modified_at = metadata.modified().ok().map(DateTime::<Utc>::from);
+        if let Some(stamp) = file_stamp(metadata.len(), modified_at) {
+            document.add_text(fields.stamp, &stamp);
+        }
+
+        writer.add_document(document)?;
+        Ok(true)
+    }
+}
+
+/// The facet for a `/`-separated folder path, `None` for the root.
+fn folder_facet(folder: &str) -> Option<Facet> {
+    let segments: Vec<_> = folder
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    (!segments.is_empty()).then(|| Facet::from_path(segments))
+}
+
+/// `None` without a modification time: such files are always re-indexed.
+fn file_stamp(size: u64, modified_at: Option<DateTime<Utc>>) -> Option<String> {
+    modified_at.map(|modified_at| {
+        format!(
+            "{size}@{}",
+            modified_at.to_rfc3339_opts(SecondsFormat::Nanos, true)
+        )
+    })
+}
+
+fn flatten_text(value: &Value, text: &mut String) {
+    match value {
+        Value::String(value) => {
+            text.push_str(value);
+            text.push('\n');
+        }
+        Value::Number(number) => {
+            text.push_str(&number.to_string());
+            text.push('\n');
+        }
+        Value::Sequence(items) => items.iter().for_each(|item| flatten_text(item, text)),
+        Value::Mapping(mapping) => mapping.values().for_each(|item| flatten_text(item, text)),
+        _ => {}
+    }
+}
+
+fn read_format(dir: &Path) -> Option<u32> {
+    fs::read_to_string(dir.join(FORMAT_FILE))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{FORMAT_FILE, SearchFilters, SearchIndex};
+    use crate::{
+        scan::scan_workspace,
+        watcher::{WorkspaceChange, WorkspaceChanged},
+    };
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Characters/Court")).expect("create characters");
+        fs::create_dir_all(root.join("Places")).expect("create places");
+        fs::write(
+            root.join("Characters/Elandra.character.md"),
+            "---\nname: Elandra Vosh\nfaction: Iron Court\n---\nA harbour pilot who lost her ship.\n",
+        )
+        .expect("write elandra");
+        fs::write(
+            root.join("Characters/Court/Oskar.character.md"),
+            "---\nname: Oskar\n---\nOskar owes Vosh a debt from the harbour wars.\n",
+        )
+        .expect("write oskar");
+        fs::write(
+            root.join("Places/Saltreach.location.md"),
+            "The harbour of Saltreach never freezes.\n",
+        )
+        .expect("write saltreach");
+        temp
+    }
+
+    fn indexed(root: &std::path::Path) -> SearchIndex {
+        let index = SearchIndex::open(root).expect("open");
+        index.rebuild().expect("rebuild");
+        index
+    }
+
+    fn paths(index: &SearchIndex, query: &str, filters: &SearchFilters) -> Vec<String> {
+        index
+            .search(query, filters, 10)
+            .expect("search")
+            .into_iter()
+            .map(|hit| hit.path)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_title_matches_first_and_highlights_snippets() {
+        let temp = workspace();
+        let index = SearchIndex::open(temp.path()).expect("open");
+        assert!(index.needs_rebuild());
+        assert_eq!(index.rebuild().expect("rebuild"), 3);
+
+        let hits = index
+            .search("vosh", &SearchFilters::default(), 10)
+            .expect("search");
+
+        let found: Vec<_> = hits.iter().map(|hit| hit.title.as_str()).collect();
+        assert_eq!(found, ["Elandra Vosh", "Oskar"]);
+        assert_eq!(hits[0].entity_type.as_deref(), Some("character"));
+        let oskar = &hits[1];
+        let (start, end) = oskar.highlights[0];
+        assert_eq!(&oskar.snippet[start..end], "Vosh");
+
+        // Frontmatter values and accents are searchable too.
+        assert_eq!(
+            paths(&index, "iron cöurt", &SearchFilters::default()),
+            ["Characters/Elandra.character.md"]
+        );
+        assert!(paths(&index, "  ", &SearchFilters::default()).is_empty());
+    }
+
+    #[test]
+    fn highlights_in_utf16_offsets() {
+        let temp = workspace();
+        fs::write(
+            temp.path().join("Places/Brine.md"),
+            "Über the cold öcean 🌊 lies Brine.\n",
+        )
+        .expect("write brine");
+        let index = indexed(temp.path());
+
+        let hits = index
+            .search("ocean lies", &SearchFilters::default(), 10)
+            .expect("search");
+
+        let units: Vec<u16> = hits[0].snippet.encode_utf16().collect();
+        let highlighted: Vec<_> = hits[0]
+            .highlights
+            .iter()
+            .map(|&(start, end)| String::from_utf16(&units[start..end]).expect("utf-16"))
+            .collect();
+        assert_eq!(highlighted, ["öcean", "lies"]);
+    }
+
+    #[test]
+    fn clamps_out_of_range_limits() {
+        let temp = workspace();
+        let index = indexed(temp.path());
+        let filters = SearchFilters::default();
+
+        assert_eq!(index.search("harbour", &filters, 0).expect("zero").len(), 1);
+        assert_eq!(
+            index
+                .search("harbour", &filters, usize::MAX)
+                .expect("huge")
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn filters_by_entity_type_and_folder() {
+        let temp = workspace();
+        let index = indexed(temp.path());
+
+        let locations = SearchFilters {
+            entity_types: vec!["location".into()],
+            folder: None,
+        };
+        assert_eq!(
+            paths(&index, "harbour", &locations),
+            ["Places/Saltreach.location.md"]
+        );
+
+        let court = SearchFilters {
+            entity_types: Vec::new(),
+            folder: Some("Characters/Court".into()),
+        };
+        assert_eq!(
+            paths(&index, "harbour", &court),
+            ["Characters/Court/Oskar.character.md"]
+        );
+
+        let characters = SearchFilters {
+            entity_types: vec!["character".into(), "faction".into()],
+            folder: Some("Characters/".into()),
+        };
+        assert_eq!(paths(&index, "harbour", &characters).len(), 2);
+    }
+
+    #[test]
+    fn applies_watcher_changes() {
+        let temp = workspace();
+        let root = temp.path();
+        let index = indexed(root);
+
+        fs::write(
+            root.join("Places/Saltreach.location.md"),
+            "A fishing town on the ice.\n",
+        )
+        .expect("rewrite");
+        let tree = scan_workspace(root).expect("scan");
+        let entry = tree
+            .files()
+            .into_iter()
+            .find(|entry| entry.path == "Places/Saltreach.location.md")
+            .cloned()
+            .expect("entry");
+        fs::remove_dir_all(root.join("Characters/Court")).expect("remove court");
+
+        index
+            .apply(&WorkspaceChanged {
+                root_path: root.display().to_string(),
+                changes: vec![
+                    WorkspaceChange::Modified { entry },
+                    WorkspaceChange::Removed {
+                        path: "Characters/Court".into(),
+                    },
+                ],
+                rescan: false,
+            })
+            .expect("apply");
+
+        let filters = SearchFilters::default();
+        assert_eq!(
+            paths(&index, "harbour", &filters),
+            ["Characters/Elandra.character.md"]
+        );
+        assert_eq!(
+            paths(&index, "fishing", &filters),
+            ["Places/Saltreach.location.md"]
+        );
+    }
+
+    #[test]
+    fn reconciles_changes_made_while_closed() {
+        let temp = workspace();
+        let root = temp.path();
+        drop(indexed(root));
+
+        fs::write(
+            root.join("Places/Saltreach.location.md"),
+            "A fishing town on the ice.\n",
+        )
+        .expect("rewrite");
+        fs::remove_file(root.join("Characters/Court/Oskar.character.md")).expect("remove");
+        fs::write(root.join("Places/Brine.md"), "Fishing nets.\n").expect("add");
+
+        let reopened = SearchIndex::open(root).expect("reopen");
+        assert!(!reopened.needs_rebuild());
+        assert_eq!(reopened.reconcile().expect("reconcile"), 3);
+        assert_eq!(reopened.reconcile().expect("reconcile again"), 0);
+
+        let filters = SearchFilters::default();
+        assert_eq!(
+            paths(&reopened, "harbour", &filters),
+            ["Characters/Elandra.character.md"]
+        );
+        assert_eq!(paths(&reopened, "fishing", &filters).len(), 2);
+    }
+
+    #[test]
+    fn rebuilds_outdated_or_corrupt_indexes() {
+        let temp = workspace();
+        let root = temp.path();
+        drop(indexed(root));
+
+        let reopened = SearchIndex::open(root).expect("reopen");
+        assert!(!reopened.needs_rebuild());
+        assert_eq!(
+            paths(&reopened, "saltreach", &SearchFilters::default()).len(),
+            1
+        );
+        drop(reopened);
+
+        let dir = root.join(".lore/cache/search");
+        fs::write(dir.join(FORMAT_FILE), "0").expect("outdated");
+        let outdated = SearchIndex::open(root).expect("outdated");
+        assert!(outdated.needs_rebuild());
+        assert!(paths(&outdated, "saltreach", &SearchFilters::default()).is_empty());
+        outdated.rebuild().expect("rebuild");
+        drop(outdated);
+
+        fs::write(dir.join("meta.json"), "not json").expect("corrupt");
+        assert!(SearchIndex::open(root).expect("corrupt").needs_rebuild());
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::write_atomic,
+    registry::{INTERNAL_DIR, SETTINGS_FILE, WorkspaceError},
+};
+
+/// The settings of one workspace, kept in `.lore/settings.toml`. Keys left
+/// out of the file take their default, so an empty file is valid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct WorkspaceSettings {
+    /// Folder new entities of a type go to, by type id, overriding the
+    /// type's own `folder`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub entity_folders: BTreeMap<String, String>,
+    pub link_style: LinkStyle,
+    /// A `strftime` pattern, e.g. `%d %B %Y`.
+    pub date_format: String,
+    /// A language tag such as `en-GB`; `None` follows the app language.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spellcheck_language: Option<String>,
+    pub export: ExportSettings,
+    /// App preferences overridden in this workspace, by preference key.
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub preferences: serde_json::Map<String, serde_json::Value>,
+    /// Keys this version does not know about, kept so saving doesn't drop them.
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            entity_folders: BTreeMap::new(),
+            link_style: LinkStyle::default(),
+            date_format: "%Y-%m-%d".to_string(),
+            spellcheck_language: None,
+            export: ExportSettings::default(),
+            preferences: serde_json::Map::new(),
+            extra: toml::Table::new(),
+        }
+    }
+}
+
+/// How new links are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStyle {
+    /// `[[Saltreach]]`
+    #[default]
+    Wiki,
+    /// `[Saltreach](Places/Saltreach.location.md)`
+    Markdown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct ExportSettings {
+    pub format: ExportFormat,
+    pub include_frontmatter: bool,
+    /// Relative to the workspace root; `None` asks on every export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Pdf,
+}
+
+/// Reads `.lore/settings.toml`, filling in defaults for everything it does
+/// not set. A missing file means all defaults.
+pub fn load_workspace_settings(
+    root: impl AsRef<Path>,
+) -> Result<WorkspaceSettings, WorkspaceError> {
+    let path = root.as_ref().join(INTERNAL_DIR).join(SETTINGS_FILE);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(WorkspaceSettings::default());
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    toml::from_str(&contents).map_err(|error| WorkspaceError::MalformedSettings {
+        path: path.display().to_string(),
+        reason: error.message().to_string(),
+    })
+}
+
+/// Writes `settings` to `.lore/settings.toml`. Preference overrides set to
+/// `null` are dropped, which restores the app-wide value.
+pub fn save_workspace_settings(
+    root: impl AsRef<Path>,
+    settings: &WorkspaceSettings,
+) -> Result<(), WorkspaceError> {
+    let root = root.as_ref();
+    if !root.is_dir() {
+        return Err(WorkspaceError::WorkspaceNotFound(
+            root.display().to_string(),
+        ));
+    }
+
+    let mut settings = settings.clone();
+    settings.preferences.retain(|_, value| !value.is_null());
+
+    let internal_dir = root.join(INTERNAL_DIR);
+    fs::create_dir_all(&internal_dir)?;
+    write_atomic(
+        &internal_dir.join(SETTINGS_FILE),
+        toml::to_string_pretty(&settings)?.as_bytes(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::{
+        ExportFormat, LinkStyle, WorkspaceSettings, load_workspace_settings,
+        save_workspace_settings,
+    };
+    use crate::{entities::create_entity, registry::WorkspaceError};
+
+    #[test]
+    fn missing_or_empty_files_are_all_defaults() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+
+        assert_eq!(
+            load_workspace_settings(root).expect("missing"),
+            WorkspaceSettings::default()
+        );
+
+        fs::create_dir_all(root.join(".lore")).expect("create internal dir");
+        fs::write(root.join(".lore/settings.toml"), "").expect("write empty");
+        let settings = load_workspace_settings(root).expect("empty");
+        assert_eq!(settings, WorkspaceSettings::default());
+        assert_eq!(settings.date_format, "%Y-%m-%d");
+    }
+
+    #[test]
+    fn merges_partial_files_with_defaults_and_keeps_unknown_keys() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join(".lore")).expect("create internal dir");
+        fs::write(
+            root.join(".lore/settings.toml"),
+            "link_style = \"markdown\"\nsync = true\n\n[export]\nformat = \"pdf\"\n\n[preferences]\nfont_size = 18\n",
+        )
+        .expect("write settings");
+
+        let mut settings = load_workspace_settings(root).expect("load");
+
+        assert_eq!(settings.link_style, LinkStyle::Markdown);
+        assert_eq!(settings.export.format, ExportFormat::Pdf);
+        assert!(!settings.export.include_frontmatter);
+        assert_eq!(settings.preferences.get("font_size"), Some(&json!(18)));
+
+        settings.spellcheck_language = Some("en-GB".into());
+        settings.preferences.insert("font_size".into(), json!(null));
+        settings.preferences.insert("theme".into(), json!("dark"));
+        save_workspace_settings(root, &settings).expect("save");
+
+        let saved = fs::read_to_string(root.join(".lore/settings.toml")).expect("read");
+        let reloaded = load_workspace_settings(root).expect("reload");
+        assert_eq!(
+            reloaded.extra.get("sync"),
+            Some(&toml::Value::Boolean(true)),
+            "{saved}"
+        );
+        assert_eq!(reloaded.spellcheck_language.as_deref(), Some("en-GB"));
+        assert_eq!(reloaded.preferences.get("font_size"), None);
+        assert_eq!(reloaded.preferences.get("theme"), Some(&json!("dark")));
+        assert_eq!(reloaded.export.format, ExportFormat::Pdf);
+    }
+
+    #[test]
+    fn reports_malformed_settings() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join(".lore")).expect("create internal dir");
+        fs::write(root.join(".lore/settings.toml"), "link_style = \"html\"\n")
+            .expect("write settings");
+
+        let error = load_workspace_settings(root).expect_err("malformed");
+
+        assert!(matches!(error, WorkspaceError::MalformedSettings { .. }));
+    }
+
+    #[test]
+    fn entity_folders_override_type_folders() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        let mut settings = WorkspaceSettings::default();
+        settings
+            .entity_folders
+            .insert("character".into(), "Cast".into());
+        save_workspace_settings(root, &settings).expect("save");
+
+        let character = create_entity(root, "character", "Maren", None).expect("character");
+        let location = create_entity(root, "location", "Saltreach", None).expect("location");
+        let explicit =
+            create_entity(root, "character", "Oskar", Some("Characters")).expect("explicit");
+
+        assert_eq!(character, "Cast/Maren.character.md");
+        assert_eq!(location, "Places/Saltreach.location.md");
+        assert_eq!(explicit, "Characters/Oskar.character.md");
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    documents::{resolve_workspace_entry, resolve_workspace_path, write_atomic},
+    migrations::copy_dir,
+    registry::{INTERNAL_DIR, TRASH_DIR, WorkspaceError},
+    scan::{classify_file, relative_path},
+};
+
+/// Metadata stored beside each trashed item.
+const ENTRY_FILE: &str = "entry.json";
+/// The trashed file or folder itself, under its trash folder.
+const ITEM_NAME: &str = "item";
+
+/// Days a trashed item is kept before [`purge_trash`] removes it on open.
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    /// Relative to the workspace root, always `/`-separated.
+    pub original_path: String,
+    pub deleted_at: DateTime<Utc>,
+    /// The user action that removed the item, such as `delete`.
+    pub deleted_by: String,
+    pub is_dir: bool,
+}
+
+/// Moves a file or folder into `.lore/trash/`. A symlink is trashed itself,
+/// not what it points to.
+pub fn move_to_trash(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+    deleted_by: &str,
+) -> Result<TrashEntry, WorkspaceError> {
+    let (root, path) = resolve_workspace_entry(root.as_ref(), path.as_ref())?;
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(WorkspaceError::PathNotFound(relative_path(&root, &path)));
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    let entry = TrashEntry {
+        id: Uuid::new_v4().to_string(),
+        original_path: relative_path(&root, &path),
+        deleted_at: Utc::now(),
+        deleted_by: deleted_by.to_string(),
+        is_dir: metadata.is_dir(),
+    };
+
+    let entry_dir = trash_dir(&root).join(&entry.id);
+    fs::create_dir_all(&entry_dir)?;
+    move_path(&path, &entry_dir.join(ITEM_NAME))?;
+
+    if let Err(error) = write_entry(&entry_dir, &entry) {
+        // Put the item back rather than leave it in the trash untracked.
+        move_path(&entry_dir.join(ITEM_NAME), &path)?;
+        fs::remove_dir_all(&entry_dir)?;
+        return Err(error);
+    }
+
+    Ok(entry)
+}
+
+/// Trashed items, most recently deleted first. Folders whose metadata is
+/// missing or unreadable are skipped.
+pub fn list_trash(root: impl AsRef<Path>) -> Result<Vec<TrashEntry>, WorkspaceError> {
+    let trash_dir = trash_dir(root.as_ref());
+    let mut entries = Vec::new();
+
+    let dir_entries = match fs::read_dir(&trash_dir) {
+        Ok(dir_entries) => dir_entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(entries),
+        Err(error) => return Err(error.into()),
+    };
+
+    for dir_entry in dir_entries {
+        if let Some(entry) = read_entry(&dir_entry?.path()) {
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+/// Moves a trashed item back to where it was, recreating missing folders.
+/// When something now occupies that path, the item is restored beside it as
+/// `Name (restored).ext`. Returns the path it was restored to.
+pub fn restore_from_trash(root: impl AsRef<Path>, id: &str) -> Result<String, WorkspaceError> {
+    let root = root.as_ref();
+    let entry_dir = entry_dir(root, id)?;
+    let entry =
+        read_entry(&entry_dir).ok_or_else(|| WorkspaceError::TrashItemNotFound(id.to_string()))?;
+
+    let (root, original) = resolve_workspace_path(root, Path::new(&entry.original_path))?;
+    let target = available_path(&original);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    move_path(&entry_dir.join(ITEM_NAME), &target)?;
+    fs::remove_dir_all(&entry_dir)?;
+
+    Ok(relative_path(&root, &target))
+}
+
+/// Permanently deletes one trashed item.
+pub fn delete_from_trash(root: impl AsRef<Path>, id: &str) -> Result<(), WorkspaceError> {
+    fs::remove_dir_all(entry_dir(root.as_ref(), id)?)?;
+    Ok(())
+}
+
+/// Permanently deletes everything in the trash, returning how many items
+/// were removed.
+pub fn empty_trash(root: impl AsRef<Path>) -> Result<usize, WorkspaceError> {
+    remove_entries(root.as_ref(), |_| true)
+}
+
+/// Permanently deletes items trashed more than `max_age` ago, returning how
+/// many were removed. An age reaching back before the earliest representable
+/// date removes nothing.
+pub fn purge_trash(root: impl AsRef<Path>, max_age: Duration) -> Result<usize, WorkspaceError> {
+    let Some(cutoff) = Utc::now().checked_sub_signed(max_age) else {
+        return Ok(0);
+    };
+    remove_entries(root.as_ref(), |entry| {
+        entry.is_some_and(|entry| entry.deleted_at < cutoff)
+    })
+}
+
+fn remove_entries(
+    root: &Path,
+    should_remove: impl Fn(Option<&TrashEntry>) -> bool,
+) -> Result<usize, WorkspaceError> {
+    let dir_entries = match fs::read_dir(trash_dir(root)) {
+        Ok(dir_entries) => dir_entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut removed = 0;
+    for dir_entry in dir_entries {
+        let path = dir_entry?.path();
+        if should_remove(read_entry(&path).as_ref()) {
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn trash_dir(root: &Path) -> PathBuf {
+    root.join(INTERNAL_DIR).join(TRASH_DIR)
+}
+
+/// Ids are UUIDs; anything else cannot name a trash folder.
+fn entry_dir(root: &Path, id: &str) -> Result<PathBuf, WorkspaceError> {
+    let not_found = || WorkspaceError::TrashItemNotFound(id.to_string());
+    let id = Uuid::parse_str(id).map_err(|_| not_found())?;
+
+    let entry_dir = trash_dir(root).join(id.to_string());
+    if entry_dir.is_dir() {
+        Ok(entry_dir)
+    } else {
+        Err(not_found())
+    }
+}
+
+fn read_entry(entry_dir: &Path) -> Option<TrashEntry> {
+    let contents = fs::read_to_string(entry_dir.join(ENTRY_FILE)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_entry(entry_dir: &Path, entry: &TrashEntry) -> Result<(), WorkspaceError> {
+    let contents = serde_json::to_string_pretty(entry).map_err(io::Error::other)?;
+    write_atomic(&entry_dir.join(ENTRY_FILE), contents.as_bytes())?;
+    Ok(())
+}
+
+/// Renames, falling back to copy and delete across filesystems.
+pub(crate) fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if fs::symlink_metadata(from)?.is_dir() {
+        copy_dir(from, to, &|_| false)?;
+        fs::remove_dir_all(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
+/// `path` when free, otherwise `Name (restored).ext`, `Name (restored 2).ext`
+/// and so on, keeping compound extensions such as `.character.md` intact.
+fn available_path(path: &Path) -> PathBuf {
+    if fs::symlink_metadata(path).is_err() {
+        return path.to_path_buf();
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (stem, extension) = split_extension(&file_name);
+
+    (1..)
+        .map(|attempt| {
+            let label = match attempt {
+                1 => "restored".to_string(),
+                attempt => format!("restored {attempt}"),
+            };
+            path.with_file_name(format!("{stem} ({label}){extension}"))
+        })
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .expect("an unused name exists")
+}
+
+/// Splits `Hero.character.md` into `Hero` and `.character.md`, and any other
+/// name at its last dot. Dotfiles keep their whole name as the stem.
+pub(crate) fn split_extension(file_name: &str) -> (&str, &str) {
+    let (_, entity_type) = classify_file(file_name);
+    let extension_len = match entity_type {
+        Some(entity_type) => entity_type.len() + ".".len() + ".md".len(),
+        None => match file_name.rfind('.') {
+            Some(0) | None => 0,
+            Some(index) => file_name.len() - index,
+        },
+    };
+
+    file_name.split_at(file_name.len() - extension_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{Duration, Utc};
+    use tempfile::tempdir;
+
+    use super::{
+        empty_trash, list_trash, move_to_trash, purge_trash, read_entry, restore_from_trash,
+        split_extension, trash_dir, write_entry,
+    };
+    use crate::registry::WorkspaceError;
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore")).expect("create internal dir");
+        fs::create_dir_all(temp.path().join("Characters")).expect("create folder");
+        fs::write(temp.path().join("Characters/Maren.character.md"), "# Maren").expect("write");
+        temp
+    }
+
+    #[test]
+    fn moves_items_into_trash_with_metadata() {
+        let temp = workspace();
+        let root = temp.path();
+
+        let entry =
+            move_to_trash(root, "Characters/Maren.character.md", "delete").expect("trashed");
+
+        assert!(!root.join("Characters/Maren.character.md").exists());
+        assert_eq!(entry.original_path, "Characters/Maren.character.md");
+        assert_eq!(entry.deleted_by, "delete");
+        assert!(!entry.is_dir);
+        assert_eq!(list_trash(root).expect("listed")[0], entry);
+        let trashed = trash_dir(root).join(&entry.id).join("item");
+        assert_eq!(fs::read_to_string(trashed).expect("read"), "# Maren");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn trashes_symlinks_rather_than_their_targets() {
+        let temp = workspace();
+        let root = temp.path();
+        let outside = tempdir().expect("outside");
+        fs::write(outside.path().join("notes.md"), "notes").expect("write outside");
+        std::os::unix::fs::symlink("Characters/Maren.character.md", root.join("Maren.md"))
+            .expect("create inner link");
+        std::os::unix::fs::symlink(outside.path().join("notes.md"), root.join("Notes.md"))
+            .expect("create outer link");
+
+        let inner = move_to_trash(root, "Maren.md", "delete").expect("trashed inner");
+        let outer = move_to_trash(root, "Notes.md", "delete").expect("trashed outer");
+
+        assert_eq!(inner.original_path, "Maren.md");
+        assert_eq!(outer.original_path, "Notes.md");
+        assert!(fs::symlink_metadata(root.join("Maren.md")).is_err());
+        assert!(root.join("Characters/Maren.character.md").is_file());
+        assert!(outside.path().join("notes.md").is_file());
+        let trashed = trash_dir(root).join(&inner.id).join("item");
+        assert!(
+            fs::symlink_metadata(trashed)
+                .expect("trashed link")
+                .file_type()
+                .is_symlink()
+        );
+    }
+
+    #[test]
+    fn restores_to_original_path_recreating_folders() {
+        let temp = workspace();
+        let root = temp.path();
+        let entry = move_to_trash(root, "Characters", "delete").expect("trashed");
+        assert!(entry.is_dir);
+
+        let restored = restore_from_trash(root, &entry.id).expect("restored");
+
+        assert_eq!(restored, "Characters");
+        assert!(root.join("Characters/Maren.character.md").is_file());
+        assert!(list_trash(root).expect("listed").is_empty());
+    }
+
+    #[test]
+    fn restores_beside_items_that_took_the_path() {
+        let temp = workspace();
+        let root = temp.path();
+        let first =
+            move_to_trash(root, "Characters/Maren.character.md", "delete").expect("trashed");
+        fs::write(root.join("Characters/Maren.character.md"), "new").expect("write");
+        fs::write(
+            root.join("Characters/Maren (restored).character.md"),
+            "taken",
+        )
+        .expect("write");
+
+        let restored = restore_from_trash(root, &first.id).expect("restored");
+
+        assert_eq!(restored, "Characters/Maren (restored 2).character.md");
+        assert_eq!(
+            fs::read_to_string(root.join(&restored)).expect("read"),
+            "# Maren"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("Characters/Maren.character.md")).expect("read"),
+            "new"
+        );
+    }
+
+    #[test]
+    fn purges_old_items_and_empties_trash() {
+        let temp = workspace();
+        let root = temp.path();
+        fs::write(root.join("Old.md"), "old").expect("write");
+        let old = move_to_trash(root, "Old.md", "delete").expect("trashed");
+        let entry_dir = trash_dir(root).join(&old.id);
+        let mut aged = read_entry(&entry_dir).expect("entry");
+        aged.deleted_at = Utc::now() - Duration::days(45);
+        write_entry(&entry_dir, &aged).expect("rewrite entry");
+        move_to_trash(root, "Characters", "delete").expect("trashed");
+
+        assert_eq!(
+            purge_trash(root, Duration::days(i64::from(u32::MAX))).expect("purged"),
+            0
+        );
+        assert_eq!(purge_trash(root, Duration::days(30)).expect("purged"), 1);
+        assert_eq!(list_trash(root).expect("listed").len(), 1);
+
+        assert_eq!(empty_trash(root).expect("emptied"), 1);
+        assert!(list_trash(root).expect("listed").is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_ids_and_missing_paths() {
+        let temp = workspace();
+        let root = temp.path();
+
+        assert!(matches!(
+            restore_from_trash(root, "../Characters"),
+            Err(WorkspaceError::TrashItemNotFound(_))
+        ));
+        assert!(matches!(
+            move_to_trash(root, "Nope.md", "delete"),
+            Err(WorkspaceError::PathNotFound(_))
+        ));
+        assert!(matches!(
+            move_to_trash(root, ".lore", "delete"),
+            Err(WorkspaceError::PathOutsideWorkspace(_))
+        ));
+    }
+
+    #[test]
+    fn splits_compound_extensions() {
+        assert_eq!(
+            split_extension("Maren.character.md"),
+            ("Maren", ".character.md")
+        );
+        assert_eq!(split_extension("map.png"), ("map", ".png"));
+        assert_eq!(split_extension(".loreignore"), (".loreignore", ""));
+        assert_eq!(split_extension("Drafts"), ("Drafts", ""));
+    }
+}
@@ -0,0 +1,628 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ignore::WorkspaceFilter,
+    manifest::{MANIFEST_EXTENSION, find_manifest},
+    migrations::{BACKUPS_DIR, copy_dir},
+    models::{CreateWorkspaceResult, WorkspaceTemplateMeta, WorkspaceTemplateSummary},
+    placeholders::TemplateVariables,
+    registry::{
+        CACHE_DIR, INTERNAL_DIR, STATE_FILE, TRASH_DIR, WorkspaceError, is_builtin_template,
+        slugify_project_name,
+    },
+    scaffold::{IGNORE_FILE, NewWorkspace, write_rendered, write_workspace_core},
+};
+
+pub(crate) const TEMPLATE_FILE: &str = "template.toml";
+/// Suffix of the folder a template is built in before it is renamed into
+/// place; see [`save_workspace_as_template`].
+const STAGING_SUFFIX: &str = ".partial";
+
+/// Per-machine state under `.lore/` that has no place in a template.
+const EXCLUDED_INTERNAL: &[&str] = &[STATE_FILE, CACHE_DIR, TRASH_DIR, BACKUPS_DIR];
+
+/// User templates sort after the built-ins unless they ask otherwise.
+const DEFAULT_SORT_ORDER: u16 = 100;
+
+/// The `template.toml` at the top of a user template folder. Every other file
+/// in the folder is skeleton content copied into new workspaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct TemplateFile {
+    pub id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct UserTemplate {
+    pub file: TemplateFile,
+    pub root: PathBuf,
+}
+
+impl UserTemplate {
+    pub fn summary(&self) -> WorkspaceTemplateSummary {
+        WorkspaceTemplateSummary {
+            id: self.file.id.clone(),
+            display_name: self.file.display_name.clone(),
+            description: self.file.description.clone(),
+            supports_creation: true,
+            is_builtin: false,
+            sort_order: self.file.sort_order.unwrap_or(DEFAULT_SORT_ORDER),
+        }
+    }
+
+    pub fn create(
+        &self,
+        workspace: &NewWorkspace<'_>,
+    ) -> Result<CreateWorkspaceResult, WorkspaceError> {
+        let result = write_workspace_core(workspace, &self.file.id)?;
+        let variables = workspace.variables(&result);
+        copy_skeleton(&self.root, workspace.root_path, &variables, true)?;
+
+        Ok(result)
+    }
+}
+
+/// Loads every template folder in `templates_dir`, ordered by folder name.
+///
+/// Folders without a readable `template.toml`, or whose id is already taken by
+/// a built-in or an earlier folder, are skipped so one broken template can't
+/// hide the rest. Hidden folders, such as leftovers of an interrupted save,
+/// are not templates.
+pub(crate) fn load_user_templates(
+    templates_dir: &Path,
+    reserved_ids: &[&str],
+) -> Vec<UserTemplate> {
+    let Ok(entries) = fs::read_dir(templates_dir) else {
+        return Vec::new();
+    };
+
+    let mut folders: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !name.ends_with(STAGING_SUFFIX)
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.join(TEMPLATE_FILE).is_file())
+        .collect();
+    folders.sort();
+
+    let mut templates: Vec<UserTemplate> = Vec::new();
+    for root in folders {
+        let Ok(file) = read_template_file(&root.join(TEMPLATE_FILE)) else {
+            continue;
+        };
+
+        let taken = reserved_ids.contains(&file.id.as_str())
+            || templates.iter().any(|template| template.file.id == file.id);
+        if file.id.trim().is_empty() || taken {
+            continue;
+        }
+
+        templates.push(UserTemplate { file, root });
+    }
+
+    templates
+}
+
+/// Saves the workspace at `root` as a new template folder in
+/// `user_templates_dir`.
+///
+/// The folder layout, `.loreignore` and `.lore/` configuration are always
+/// kept; document files are copied only with `include_content`. The manifest,
+/// `.lore/state.json`, caches, trash, backups, anything `.loreignore`
+/// excludes and symlinks are left behind.
+pub fn save_workspace_as_template(
+    root: impl AsRef<Path>,
+    template_meta: WorkspaceTemplateMeta,
+    include_content: bool,
+    user_templates_dir: &Path,
+) -> Result<WorkspaceTemplateSummary, WorkspaceError> {
+    let root = root.as_ref();
+    find_manifest(root)?;
+
+    let display_name = template_meta.display_name.trim();
+    if display_name.is_empty() {
+        return Err(WorkspaceError::EmptyTemplateName);
+    }
+
+    let id = template_meta
+        .id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(slugify_project_name)
+        .unwrap_or_else(|| slugify_project_name(display_name));
+
+    // Build next to the destination and rename at the end, so a failure never
+    // leaves a half-written template in the list. Whatever an interrupted
+    // save left behind goes first.
+    let staging = user_templates_dir.join(format!(".{id}{STAGING_SUFFIX}"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+
+    let destination = user_templates_dir.join(&id);
+    let taken = is_builtin_template(&id)
+        || destination.exists()
+        || load_user_templates(user_templates_dir, &[])
+            .iter()
+            .any(|template| template.file.id == id);
+    if taken {
+        return Err(WorkspaceError::TemplateExists(id));
+    }
+
+    let template = UserTemplate {
+        file: TemplateFile {
+            id: id.clone(),
+            display_name: display_name.to_string(),
+            description: template_meta.description.trim().to_string(),
+            sort_order: template_meta.sort_order,
+        },
+        root: destination,
+    };
+
+    let written = export_workspace(root, &staging, include_content).and_then(|()| {
+        fs::write(
+            staging.join(TEMPLATE_FILE),
+            toml::to_string_pretty(&template.file).map_err(io::Error::other)?,
+        )
+    });
+    if let Err(error) = written {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(error.into());
+    }
+
+    fs::rename(&staging, &template.root)?;
+
+    Ok(template.summary())
+}
+
+fn export_workspace(root: &Path, to: &Path, include_content: bool) -> io::Result<()> {
+    let filter = WorkspaceFilter::load(root)?;
+    export_dir(&filter, root, to, include_content)
+}
+
+/// Copies the folders `filter` keeps, and with `include_content` their
+/// files. Symlinks are skipped: they may point outside the workspace.
+fn export_dir(
+    filter: &WorkspaceFilter,
+    from: &Path,
+    to: &Path,
+    include_content: bool,
+) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    let top_level = from == filter.root();
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let target = to.join(&name);
+
+        if top_level && name == INTERNAL_DIR {
+            export_internal_dir(&path, &target)?;
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() || filter.is_ignored_entry(&path, file_type.is_dir()) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            export_dir(filter, &path, &target, include_content)?;
+            continue;
+        }
+
+        let is_manifest = path
+            .extension()
+            .is_some_and(|extension| extension == MANIFEST_EXTENSION);
+        if top_level && is_manifest {
+            continue;
+        }
+
+        if include_content || name == IGNORE_FILE {
+            fs::copy(&path, &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn export_internal_dir(from: &Path, to: &Path) -> io::Result<()> {
+    copy_dir(from, to, &|path| {
+        path.parent() == Some(from)
+            && path
+                .file_name()
+                .is_some_and(|name| EXCLUDED_INTERNAL.iter().any(|excluded| name == *excluded))
+    })
+}
+
+fn read_template_file(path: &Path) -> Result<TemplateFile, WorkspaceError> {
+    let contents = fs::read_to_string(path)?;
+
+    toml::from_str(&contents).map_err(|error| WorkspaceError::MalformedTemplate {
+        path: path.display().to_string(),
+        reason: error.message().to_string(),
+    })
+}
+
+/// Copies a template folder's skeleton into `to`, filling in placeholders and
+/// leaving out `template.toml` and any workspace manifest, which `create`
+/// writes fresh.
+fn copy_skeleton(
+    from: &Path,
+    to: &Path,
+    variables: &TemplateVariables,
+    top_level: bool,
+) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = to.join(variables.render_file_name(&entry.file_name().to_string_lossy()));
+
+        // A link could point anywhere on the machine that saved the template.
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            copy_skeleton(&path, &target, variables, false)?;
+            continue;
+        }
+
+        let is_manifest = path
+            .extension()
+            .is_some_and(|extension| extension == MANIFEST_EXTENSION);
+        if top_level && (entry.file_name() == TEMPLATE_FILE || is_manifest) {
+            continue;
+        }
+
+        write_rendered(&target, &fs::read(&path)?, variables)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::save_workspace_as_template;
+    use crate::{
+        CreateWorkspaceRequest, WorkspaceTemplateMeta, create_workspace_from,
+        list_workspace_templates_from, registry::WorkspaceError,
+    };
+
+    fn create_novel(parent_path: &Path) -> std::path::PathBuf {
+        let result = create_workspace_from(
+            CreateWorkspaceRequest {
+                name: "Salt Road".to_string(),
+                parent_path: parent_path.display().to_string(),
+                template_id: "novel".to_string(),
+                app_version: "0.1.0".to_string(),
+                author: None,
+            },
+            &parent_path.join("no-templates"),
+        )
+        .expect("workspace created");
+        let root = std::path::PathBuf::from(result.root_path);
+        fs::create_dir_all(root.join(".lore/cache")).expect("create cache");
+        fs::write(root.join(".lore/cache/index.bin"), "cache").expect("seed cache");
+        fs::create_dir_all(root.join(".lore/types")).expect("create types");
+        fs::write(root.join(".lore/types/scene.toml"), "id = \"scene\"\n").expect("seed type");
+        root
+    }
+
+    fn meta(display_name: &str) -> WorkspaceTemplateMeta {
+        WorkspaceTemplateMeta {
+            id: None,
+            display_name: display_name.to_string(),
+            description: "Tuned layout.".to_string(),
+            sort_order: None,
+        }
+    }
+
+    fn seed_template(templates_dir: &Path, folder: &str, template_toml: &str) {
+        let root = templates_dir.join(folder);
+        fs::create_dir_all(root.join("Campaigns")).expect("create skeleton");
+        fs::write(root.join("template.toml"), template_toml).expect("seed template.toml");
+        fs::write(root.join("Campaigns/Session 00.md"), "# Session zero\n")
+            .expect("seed skeleton file");
+    }
+
+    #[test]
+    fn lists_user_templates_after_builtins() {
+        let temp = tempdir().expect("tempdir");
+        seed_template(
+            temp.path(),
+            "campaign",
+            "id = \"campaign\"\ndisplay_name = \"Tabletop campaign\"\ndescription = \"Sessions and NPCs.\"\n",
+        );
+
+        let templates = list_workspace_templates_from(temp.path());
+
+        assert_eq!(templates.len(), 5);
+        assert!(templates[..4].iter().all(|template| template.is_builtin));
+        let custom = &templates[4];
+        assert_eq!(custom.id, "campaign");
+        assert_eq!(custom.display_name, "Tabletop campaign");
+        assert!(!custom.is_builtin);
+        assert!(custom.supports_creation);
+    }
+
+    #[test]
+    fn skips_malformed_and_shadowing_templates() {
+        let temp = tempdir().expect("tempdir");
+        seed_template(temp.path(), "broken", "display_name = \"No id\"\n");
+        seed_template(
+            temp.path(),
+            "novel-copy",
+            "id = \"novel\"\ndisplay_name = \"Not the real novel\"\n",
+        );
+        fs::create_dir_all(temp.path().join("no-template-file")).expect("create folder");
+
+        let templates = list_workspace_templates_from(temp.path());
+
+        assert_eq!(templates.len(), 4);
+        assert!(templates.iter().all(|template| template.is_builtin));
+    }
+
+    #[test]
+    fn creates_workspace_from_user_template() {
+        let temp = tempdir().expect("tempdir");
+        let templates_dir = temp.path().join("templates");
+        seed_template(
+            &templates_dir,
+            "campaign",
+            "id = \"campaign\"\ndisplay_name = \"Tabletop campaign\"\n",
+        );
+        let parent_path = temp.path().join("Lore");
+        let root_path = parent_path.join("night-market");
+
+        let result = create_workspace_from(
+            CreateWorkspaceRequest {
+                name: "Night Market".to_string(),
+                parent_path: parent_path.display().to_string(),
+                template_id: "campaign".to_string(),
+                app_version: "0.1.0".to_string(),
+                author: None,
+            },
+            &templates_dir,
+        )
+        .expect("workspace created");
+
+        assert_eq!(result.template_id, "campaign");
+        assert!(root_path.join("Campaigns/Session 00.md").is_file());
+        assert!(root_path.join(".lore/settings.toml").is_file());
+        assert!(!root_path.join("template.toml").exists());
+        let manifest =
+            fs::read_to_string(root_path.join("night-market.lore")).expect("read manifest");
+        assert!(manifest.contains("template_id = \"campaign\""));
+    }
+
+    #[test]
+    fn fills_placeholders_in_skeleton_names_and_contents() {
+        let temp = tempdir().expect("tempdir");
+        let templates_dir = temp.path().join("templates");
+        let template_root = templates_dir.join("journal");
+        fs::create_dir_all(template_root.join("{{slug}}-notes")).expect("create skeleton");
+        fs::write(
+            template_root.join("template.toml"),
+            "id = \"journal\"\ndisplay_name = \"Journal\"\n",
+        )
+        .expect("seed template.toml");
+        fs::write(
+            template_root.join("{{slug}}-notes/{{workspace_name}}.md"),
+            "# {{workspace_name}}\nKept by {{author}} since {{created_at}}. {{unknown}}\n",
+        )
+        .expect("seed skeleton file");
+        let parent_path = temp.path().join("Lore");
+
+        let result = create_workspace_from(
+            CreateWorkspaceRequest {
+                name: "Night Market".to_string(),
+                parent_path: parent_path.display().to_string(),
+                template_id: "journal".to_string(),
+                app_version: "0.1.0".to_string(),
+                author: Some("Ada".to_string()),
+            },
+            &templates_dir,
+        )
+        .expect("workspace created");
+
+        let note =
+            fs::read_to_string(parent_path.join("night-market/night-market-notes/Night Market.md"))
+                .expect("read rendered note");
+        assert_eq!(
+            note,
+            format!(
+                "# Night Market\nKept by Ada since {}. {{{{unknown}}}}\n",
+                result.created_at
+            )
+        );
+    }
+
+    #[test]
+    fn saves_workspace_structure_as_template() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+
+        let summary =
+            save_workspace_as_template(&root, meta("My Novel Layout"), false, &templates_dir)
+                .expect("template saved");
+
+        assert_eq!(summary.id, "my-novel-layout");
+        assert!(!summary.is_builtin);
+        let saved = templates_dir.join("my-novel-layout");
+        assert!(saved.join("template.toml").is_file());
+        assert!(saved.join("Chapters").is_dir());
+        assert!(!saved.join("Chapters/Chapter 01.md").exists());
+        assert!(saved.join(".loreignore").is_file());
+        assert!(saved.join(".lore/settings.toml").is_file());
+        assert!(saved.join(".lore/types/scene.toml").is_file());
+        assert!(!saved.join(".lore/state.json").exists());
+        assert!(!saved.join(".lore/cache").exists());
+        assert!(!saved.join("salt-road.lore").exists());
+
+        let listed = list_workspace_templates_from(&templates_dir);
+        assert!(
+            listed
+                .iter()
+                .any(|template| template.id == "my-novel-layout")
+        );
+    }
+
+    #[test]
+    fn saved_template_with_content_creates_new_workspaces() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+        save_workspace_as_template(&root, meta("Novel Kit"), true, &templates_dir)
+            .expect("template saved");
+        let parent_path = temp.path().join("New");
+
+        let result = create_workspace_from(
+            CreateWorkspaceRequest {
+                name: "Second Book".to_string(),
+                parent_path: parent_path.display().to_string(),
+                template_id: "novel-kit".to_string(),
+                app_version: "0.1.0".to_string(),
+                author: None,
+            },
+            &templates_dir,
+        )
+        .expect("workspace created");
+
+        let new_root = parent_path.join("second-book");
+        assert_eq!(result.template_id, "novel-kit");
+        assert!(new_root.join("Chapters/Chapter 01.md").is_file());
+        assert!(new_root.join("second-book.lore").is_file());
+        assert!(!new_root.join("salt-road.lore").exists());
+    }
+
+    #[test]
+    fn leaves_ignored_folders_and_symlinks_out_of_templates() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+        fs::write(root.join(".loreignore"), "node_modules/\n").expect("write ignore");
+        fs::create_dir_all(root.join("node_modules/left-pad")).expect("create ignored");
+        fs::write(root.join("node_modules/left-pad/index.js"), "").expect("write ignored");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("Chapters"), root.join("Linked"))
+            .expect("create symlink");
+
+        save_workspace_as_template(&root, meta("Novel Kit"), true, &templates_dir)
+            .expect("template saved");
+
+        let saved = templates_dir.join("novel-kit");
+        assert!(saved.join("Chapters/Chapter 01.md").is_file());
+        assert!(saved.join(".loreignore").is_file());
+        assert!(!saved.join("node_modules").exists());
+        assert!(!saved.join("Linked").exists());
+    }
+
+    #[test]
+    fn ignores_and_clears_interrupted_saves() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+        seed_template(
+            &templates_dir,
+            ".novel-kit.partial",
+            "id = \"novel-kit\"\ndisplay_name = \"Half\"\n",
+        );
+
+        assert!(
+            !list_workspace_templates_from(&templates_dir)
+                .iter()
+                .any(|template| template.id == "novel-kit")
+        );
+
+        save_workspace_as_template(&root, meta("Novel Kit"), false, &templates_dir)
+            .expect("template saved");
+
+        assert!(!templates_dir.join(".novel-kit.partial").exists());
+        let listed = list_workspace_templates_from(&templates_dir);
+        let saved = listed
+            .iter()
+            .find(|template| template.id == "novel-kit")
+            .expect("listed");
+        assert_eq!(saved.display_name, "Novel Kit");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn leaves_symlinks_in_lore_dir_out_of_templates() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+        fs::write(temp.path().join("secret.txt"), "secret").expect("write secret");
+        std::os::unix::fs::symlink(
+            temp.path().join("secret.txt"),
+            root.join(".lore/types/linked.toml"),
+        )
+        .expect("create symlink");
+
+        save_workspace_as_template(&root, meta("Novel Kit"), false, &templates_dir)
+            .expect("template saved");
+
+        let saved = templates_dir.join("novel-kit");
+        assert!(saved.join(".lore/types/scene.toml").is_file());
+        assert!(!saved.join(".lore/types/linked.toml").exists());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_existing_templates() {
+        let temp = tempdir().expect("tempdir");
+        let root = create_novel(temp.path());
+        let templates_dir = temp.path().join("templates");
+        save_workspace_as_template(&root, meta("Novel Kit"), false, &templates_dir)
+            .expect("template saved");
+
+        let duplicate = save_workspace_as_template(&root, meta("Novel Kit"), false, &templates_dir)
+            .expect_err("must fail");
+        let builtin = save_workspace_as_template(&root, meta("Blank"), false, &templates_dir)
+            .expect_err("must fail");
+
+        assert!(matches!(duplicate, WorkspaceError::TemplateExists(_)));
+        assert!(matches!(builtin, WorkspaceError::TemplateExists(_)));
+    }
+
+    #[test]
+    fn rejects_template_missing_from_templates_dir() {
+        let temp = tempdir().expect("tempdir");
+
+        let error = create_workspace_from(
+            CreateWorkspaceRequest {
+                name: "Night Market".to_string(),
+                parent_path: temp.path().display().to_string(),
+                template_id: "campaign".to_string(),
+                app_version: "0.1.0".to_string(),
+                author: None,
+            },
+            &temp.path().join("missing"),
+        )
+        .expect_err("must fail");
+
+        assert!(matches!(error, WorkspaceError::UnknownTemplate(_)));
+    }
+}
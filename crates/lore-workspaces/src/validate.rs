@@ -0,0 +1,529 @@
+use std::{fs, path::Path};
+
+use chrono::{DateTime, NaiveDate};
+use lore_core::{Document, DocumentError, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    documents::resolve_workspace_path,
+    index::WorkspaceIndex,
+    links::link_target,
+    registry::WorkspaceError,
+    scan::relative_path,
+    schema::{EntitySchema, FieldKind, FieldSchema, TYPE_KEY},
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticCode {
+    /// The frontmatter is not valid YAML.
+    InvalidFrontmatter,
+    /// A file in `.lore/types/` could not be read as an entity type.
+    MalformedType,
+    /// `type:` names an entity type the workspace does not define.
+    UnknownType,
+    MissingField,
+    WrongType,
+    /// An enum value outside the field's `options`.
+    UnknownOption,
+    /// A number, or a text or list length, outside `min`/`max`.
+    OutOfRange,
+    /// A link to a document that does not exist.
+    BrokenLink,
+    /// A link to a document of another entity type than the field's `target`.
+    WrongLinkTarget,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// Relative to the workspace root, always `/`-separated.
+    pub path: String,
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    /// The frontmatter field the problem is about.
+    pub field: Option<String>,
+    /// Line in the file, counting from 1, when known.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+/// Checks the frontmatter of `path` against its entity type. Files without
+/// a known type only report frontmatter that cannot be parsed.
+pub fn validate_document(
+    root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<Vec<Diagnostic>, WorkspaceError> {
+    let (root, path) = resolve_workspace_path(root.as_ref(), path.as_ref())?;
+    let relative = relative_path(&root, &path);
+    let content = fs::read_to_string(&path).map_err(|error| match error.kind() {
+        std::io::ErrorKind::NotFound => WorkspaceError::DocumentNotFound(relative.clone()),
+        _ => error.into(),
+    })?;
+
+    // Only link targets are needed from the rest of the workspace.
+    let index = WorkspaceIndex::load_cached(&root)?;
+    Ok(Validator { index: &index }.check(&relative, &Document::parse(&content)))
+}
+
+/// Validates every Markdown file in the workspace, plus the entity type
+/// files themselves. Results are ordered by path and line.
+pub fn validate_workspace(root: impl AsRef<Path>) -> Result<Vec<Diagnostic>, WorkspaceError> {
+    let root = root.as_ref();
+    let root = root
+        .canonicalize()
+        .map_err(|_| WorkspaceError::WorkspaceNotFound(root.display().to_string()))?;
+    let index = WorkspaceIndex::load(&root)?;
+    let validator = Validator { index: &index };
+
+    let mut diagnostics: Vec<Diagnostic> = index
+        .types
+        .errors
+        .iter()
+        .filter_map(|error| match error {
+            WorkspaceError::MalformedEntityType { path, reason } => Some(Diagnostic {
+                path: relative_path(&root, Path::new(path)),
+                severity: Severity::Error,
+                code: DiagnosticCode::MalformedType,
+                field: None,
+                line: None,
+                column: None,
+                message: reason.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    for document in &index.documents {
+        diagnostics.extend(validator.check(&document.path, &document.document));
+    }
+
+    diagnostics.sort_by(|left, right| {
+        (&left.path, left.line, left.severity).cmp(&(&right.path, right.line, right.severity))
+    });
+    Ok(diagnostics)
+}
+
+/// Checks documents against the schemas and link targets of an index.
+struct Validator<'a> {
+    index: &'a WorkspaceIndex,
+}
+
+impl Validator<'_> {
+    fn check(&self, path: &str, document: &Result<Document, DocumentError>) -> Vec<Diagnostic> {
+        let mut report = Report {
+            path,
+            diagnostics: Vec::new(),
+        };
+
+        let document = match document {
+            Ok(document) => document,
+            Err(error) => {
+                let (line, column) = match error {
+                    DocumentError::UnclosedFrontmatter { line }
+                    | DocumentError::NotAMapping { line } => (Some(*line), None),
+                    DocumentError::InvalidFrontmatter { line, column, .. } => {
+                        (Some(*line), Some(*column))
+                    }
+                    DocumentError::Serialize(_) => (None, None),
+                };
+                report.push(Diagnostic {
+                    path: path.to_string(),
+                    severity: Severity::Error,
+                    code: DiagnosticCode::InvalidFrontmatter,
+                    field: None,
+                    line,
+                    column,
+                    message: error.to_string(),
+                });
+                return report.diagnostics;
+            }
+        };
+
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let schema = match document.get(TYPE_KEY) {
+            None => self.index.types.for_document(file_name, None),
+            Some(Value::String(id)) => match self.index.types.get(id) {
+                Some(schema) => Some(schema),
+                None => {
+                    report.add(
+                        document,
+                        TYPE_KEY,
+                        Severity::Warning,
+                        DiagnosticCode::UnknownType,
+                        format!("'{id}' is not an entity type of this workspace."),
+                    );
+                    None
+                }
+            },
+            Some(_) => {
+                report.add(
+                    document,
+                    TYPE_KEY,
+                    Severity::Error,
+                    DiagnosticCode::WrongType,
+                    "`type` must be the id of an entity type.".to_string(),
+                );
+                None
+            }
+        };
+
+        if let Some(schema) = schema {
+            self.check_fields(schema, document, &mut report);
+        }
+
+        report.diagnostics
+    }
+
+    fn check_fields(&self, schema: &EntitySchema, document: &Document, report: &mut Report) {
+        for field in &schema.fields {
+            match document.get(&field.name) {
+                None | Some(Value::Null) => {
+                    if field.required {
+                        report.add(
+                            document,
+                            &field.name,
+                            Severity::Error,
+                            DiagnosticCode::MissingField,
+                            format!(
+                                "A {} needs a `{}`.",
+                                schema.display_name.to_lowercase(),
+                                field.name
+                            ),
+                        );
+                    }
+                }
+                Some(value) => {
+                    let problems = self.check_value(field, field.kind, value);
+                    for (severity, code, message) in problems {
+                        report.add(document, &field.name, severity, code, message);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_value(
+        &self,
+        field: &FieldSchema,
+        kind: FieldKind,
+        value: &Value,
+    ) -> Vec<(Severity, DiagnosticCode, String)> {
+        let wrong_type = |expected: &str| {
+            vec![(
+                Severity::Error,
+                DiagnosticCode::WrongType,
+                format!("`{}` must be {expected}.", field.name),
+            )]
+        };
+
+        match kind {
+            FieldKind::String => match scalar_text(value) {
+                Some(text) => field.out_of_range(text.chars().count() as f64, "characters"),
+                None => wrong_type("text"),
+            },
+            FieldKind::Enum => match scalar_text(value) {
+                Some(text) if field.options.contains(&text) => Vec::new(),
+                Some(text) => vec![(
+                    Severity::Error,
+                    DiagnosticCode::UnknownOption,
+                    format!(
+                        "'{text}' is not a valid `{}`; expected one of: {}.",
+                        field.name,
+                        field.options.join(", ")
+                    ),
+                )],
+                None => wrong_type(&format!("one of: {}", field.options.join(", "))),
+            },
+            FieldKind::Number => match value.as_f64() {
+                Some(number) => field.out_of_range(number, ""),
+                None => wrong_type("a number"),
+            },
+            FieldKind::Date => match value.as_str() {
+                Some(text) if is_date(text) => Vec::new(),
+                _ => wrong_type("a date such as 2025-03-01"),
+            },
+            FieldKind::Link => match value.as_str().and_then(link_target) {
+                Some(target) => self.check_link(field, &target),
+                None => wrong_type("a link such as \"[[Name]]\""),
+            },
+            FieldKind::List => match value.as_sequence() {
+                Some(items) => {
+                    let mut problems = field.out_of_range(items.len() as f64, "items");
+                    // `min` and `max` bound the list, not each item.
+                    let item_field = FieldSchema {
+                        min: None,
+                        max: None,
+                        ..field.clone()
+                    };
+                    for item in items {
+                        problems.extend(self.check_value(&item_field, field.value_kind(), item));
+                    }
+                    problems
+                }
+                None => wrong_type("a list"),
+            },
+        }
+    }
+
+    fn check_link(
+        &self,
+        field: &FieldSchema,
+        target: &str,
+    ) -> Vec<(Severity, DiagnosticCode, String)> {
+        let Some(path) = self.index.targets.resolve(target) else {
+            return vec![(
+                Severity::Warning,
+                DiagnosticCode::BrokenLink,
+                format!(
+                    "`{}` links to '{target}', which does not exist.",
+                    field.name
+                ),
+            )];
+        };
+
+        match &field.target {
+            Some(expected) if self.index.targets.entity_type(path) != Some(expected.as_str()) => {
+                vec![(
+                    Severity::Warning,
+                    DiagnosticCode::WrongLinkTarget,
+                    format!(
+                        "`{}` should link to a {expected}, but '{target}' is not one.",
+                        field.name
+                    ),
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FieldSchema {
+    fn out_of_range(&self, amount: f64, unit: &str) -> Vec<(Severity, DiagnosticCode, String)> {
+        let unit = if unit.is_empty() {
+            String::new()
+        } else {
+            format!(" {unit}")
+        };
+        let message = match (self.min, self.max) {
+            (Some(min), _) if amount < min => {
+                format!("`{}` must be at least {min}{unit}.", self.name)
+            }
+            (_, Some(max)) if amount > max => {
+                format!("`{}` must be at most {max}{unit}.", self.name)
+            }
+            _ => return Vec::new(),
+        };
+
+        vec![(Severity::Warning, DiagnosticCode::OutOfRange, message)]
+    }
+}
+
+struct Report<'a> {
+    path: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Report<'_> {
+    fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// A problem with `field`, located on its line, or on the opening fence
+    /// when the field is missing.
+    fn add(
+        &mut self,
+        document: &Document,
+        field: &str,
+        severity: Severity,
+        code: DiagnosticCode,
+        message: String,
+    ) {
+        self.push(Diagnostic {
+            path: self.path.to_string(),
+            severity,
+            code,
+            field: Some(field.to_string()),
+            line: Some(document.key_line(field).unwrap_or(1)),
+            column: None,
+            message,
+        });
+    }
+}
+
+/// Text, numbers and booleans all read as text; YAML turns `name: 1984`
+/// into a number.
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn is_date(text: &str) -> bool {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok()
+        || DateTime::parse_from_rfc3339(text).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{DiagnosticCode, Severity, validate_document, validate_workspace};
+
+    fn workspace() -> tempfile::TempDir {
+        let temp = tempdir().expect("tempdir");
+        fs::create_dir_all(temp.path().join(".lore/types")).expect("create types dir");
+        fs::create_dir_all(temp.path().join("Places")).expect("create places");
+        fs::write(
+            temp.path().join("Places/Saltreach.location.md"),
+            "---\nname: Saltreach\npopulation: 4200\n---\n",
+        )
+        .expect("write location");
+        temp
+    }
+
+    #[test]
+    fn accepts_valid_entities() {
+        let temp = workspace();
+        fs::write(
+            temp.path().join("Maren.md"),
+            "---\ntype: character\nname: Maren Holt\nstatus: active\nhome: \"[[Saltreach]]\"\nallies: []\n---\n",
+        )
+        .expect("write");
+
+        let diagnostics = validate_document(temp.path(), "Maren.md").expect("validated");
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn reports_schema_violations_with_locations() {
+        let temp = workspace();
+        fs::write(
+            temp.path().join("Maren.character.md"),
+            "---\nstatus: missing\nhome: \"[[Nowhere]]\"\nallies: \"[[Saltreach]]\"\n---\n",
+        )
+        .expect("write");
+
+        let diagnostics = validate_document(temp.path(), "Maren.character.md").expect("validated");
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.field.as_deref().unwrap_or_default(),
+                    diagnostic.code,
+                    diagnostic.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("name", DiagnosticCode::MissingField, Some(1)),
+                ("status", DiagnosticCode::UnknownOption, Some(2)),
+                ("home", DiagnosticCode::BrokenLink, Some(3)),
+                ("allies", DiagnosticCode::WrongType, Some(4)),
+            ]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[2].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn checks_numbers_dates_and_link_targets() {
+        let temp = workspace();
+        let types_dir = temp.path().join(".lore/types");
+        fs::write(
+            types_dir.join("event.toml"),
+            "id = \"event\"\ndisplay_name = \"Event\"\n\n[[fields]]\nname = \"date\"\nkind = \"date\"\n\n[[fields]]\nname = \"casualties\"\nkind = \"number\"\nmin = 0\n\n[[fields]]\nname = \"led_by\"\nkind = \"link\"\ntarget = \"character\"\n",
+        )
+        .expect("write type");
+        fs::write(
+            temp.path().join("Flood.event.md"),
+            "---\ndate: last spring\ncasualties: -3\nled_by: \"[[Saltreach]]\"\n---\n",
+        )
+        .expect("write");
+
+        let diagnostics = validate_document(temp.path(), "Flood.event.md").expect("validated");
+
+        let codes: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                DiagnosticCode::WrongType,
+                DiagnosticCode::OutOfRange,
+                DiagnosticCode::WrongLinkTarget,
+            ]
+        );
+    }
+
+    #[test]
+    fn bounds_list_length_not_items() {
+        let temp = workspace();
+        fs::write(
+            temp.path().join(".lore/types/faction.toml"),
+            "id = \"faction\"\ndisplay_name = \"Faction\"\n\n[[fields]]\nname = \"tags\"\nkind = \"list\"\nitems = \"string\"\nmax = 2\n",
+        )
+        .expect("write type");
+        fs::write(
+            temp.path().join("Court.faction.md"),
+            "---\ntags: [smugglers, merchants]\n---\n",
+        )
+        .expect("write court");
+        fs::write(
+            temp.path().join("Guild.faction.md"),
+            "---\ntags: [a, b, c]\n---\n",
+        )
+        .expect("write guild");
+
+        let court = validate_document(temp.path(), "Court.faction.md").expect("validated");
+        let guild = validate_document(temp.path(), "Guild.faction.md").expect("validated");
+
+        assert!(court.is_empty(), "{court:?}");
+        assert_eq!(guild.len(), 1);
+        assert_eq!(guild[0].code, DiagnosticCode::OutOfRange);
+        assert_eq!(guild[0].message, "`tags` must be at most 2 items.");
+    }
+
+    #[test]
+    fn validates_the_whole_workspace() {
+        let temp = workspace();
+        fs::write(temp.path().join(".lore/types/broken.toml"), "id = ").expect("write type");
+        fs::write(temp.path().join("Broken.md"), "---\nname: [oops\n---\n").expect("write");
+        fs::write(temp.path().join("Ghost.md"), "---\ntype: ghost\n---\n").expect("write");
+        fs::write(temp.path().join("Notes.md"), "Plain notes.\n").expect("write");
+
+        let diagnostics = validate_workspace(temp.path()).expect("validated");
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.path.as_str(), diagnostic.code))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (".lore/types/broken.toml", DiagnosticCode::MalformedType),
+                ("Broken.md", DiagnosticCode::InvalidFrontmatter),
+                ("Ghost.md", DiagnosticCode::UnknownType),
+            ]
+        );
+        assert!(diagnostics[1].line.is_some());
+    }
+}
@@ -0,0 +1,571 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ignore::WorkspaceFilter,
+    registry::WorkspaceError,
+    scaffold::IGNORE_FILE,
+    scan::{EntryKind, WorkspaceEntry, read_entry, to_slash_path},
+};
+
+/// Quiet period after the last filesystem event before a batch is emitted.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Longest a continuous burst (a large git checkout) is held back before the
+/// changes seen so far are emitted anyway.
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WorkspaceChange {
+    /// New folders include everything already inside them.
+    Created {
+        entry: WorkspaceEntry,
+    },
+    Modified {
+        entry: WorkspaceEntry,
+    },
+    Removed {
+        path: String,
+    },
+    Renamed {
+        from: String,
+        to: String,
+        entry: WorkspaceEntry,
+    },
+}
+
+/// One debounced batch of changes, with paths relative to the workspace root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceChanged {
+    pub root_path: String,
+    pub changes: Vec<WorkspaceChange>,
+    /// Set when `.loreignore` changed or the OS dropped events; the tree
+    /// should then be reloaded with [`scan_workspace`](crate::scan_workspace).
+    pub rescan: bool,
+}
+
+/// Watches a workspace until dropped, reporting changes through a callback on
+/// a background thread.
+pub struct WorkspaceWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl WorkspaceWatcher {
+    pub fn start<F>(root: impl AsRef<Path>, on_change: F) -> Result<Self, WorkspaceError>
+    where
+        F: Fn(WorkspaceChanged) + Send + 'static,
+    {
+        Self::with_debounce(root, DEFAULT_DEBOUNCE, on_change)
+    }
+
+    pub fn with_debounce<F>(
+        root: impl AsRef<Path>,
+        debounce: Duration,
+        on_change: F,
+    ) -> Result<Self, WorkspaceError>
+    where
+        F: Fn(WorkspaceChanged) + Send + 'static,
+    {
+        let root = root.as_ref().to_path_buf();
+        if !root.is_dir() {
+            return Err(WorkspaceError::WorkspaceNotFound(
+                root.display().to_string(),
+            ));
+        }
+
+        let worker = Worker {
+            canonical_root: root.canonicalize().ok(),
+            filter: WorkspaceFilter::load(&root)?,
+            root: root.clone(),
+            debounce,
+            on_change,
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+
+        thread::Builder::new()
+            .name("lore-workspace-watcher".to_string())
+            .spawn(move || worker.run(receiver))?;
+
+        Ok(Self {
+            root,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+struct Worker<F> {
+    root: PathBuf,
+    /// Some platforms report resolved paths (e.g. `/private/var` on macOS).
+    canonical_root: Option<PathBuf>,
+    filter: WorkspaceFilter,
+    debounce: Duration,
+    on_change: F,
+}
+
+impl<F> Worker<F>
+where
+    F: Fn(WorkspaceChanged),
+{
+    /// Runs until the watcher is dropped, which closes the channel.
+    fn run(mut self, receiver: Receiver<notify::Result<Event>>) {
+        while let Ok(first) = receiver.recv() {
+            let mut batch = RawBatch::default();
+            batch.add(first);
+            let deadline = Instant::now() + MAX_BATCH_DELAY;
+
+            loop {
+                let wait = self
+                    .debounce
+                    .min(deadline.saturating_duration_since(Instant::now()));
+                match receiver.recv_timeout(wait) {
+                    Ok(message) => batch.add(message),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            if let Some(changed) = self.resolve(batch) {
+                (self.on_change)(changed);
+            }
+        }
+    }
+
+    fn resolve(&mut self, batch: RawBatch) -> Option<WorkspaceChanged> {
+        let (changes, mut rescan) = batch.finish();
+
+        if changes.iter().any(RawChange::touches_ignore_file) {
+            rescan = true;
+            if let Ok(filter) = WorkspaceFilter::load(&self.root) {
+                self.filter = filter;
+            }
+        }
+
+        let changes: Vec<_> = changes
+            .into_iter()
+            .filter_map(|change| self.resolve_change(change))
+            .collect();
+
+        if changes.is_empty() && !rescan {
+            return None;
+        }
+
+        Some(WorkspaceChanged {
+            root_path: self.root.display().to_string(),
+            changes,
+            rescan,
+        })
+    }
+
+    fn resolve_change(&self, change: RawChange) -> Option<WorkspaceChange> {
+        match change {
+            RawChange::Created(path) => {
+                let entry = self.entry(&self.relative(&path)?)?;
+                Some(WorkspaceChange::Created { entry })
+            }
+            RawChange::Modified(path) => {
+                let relative = self.relative(&path)?;
+                if fs::symlink_metadata(&path).is_err() {
+                    return self.removed(relative);
+                }
+
+                let entry = self.entry(&relative)?;
+                // Folder timestamps change with every child event already reported.
+                (entry.kind != EntryKind::Folder).then_some(WorkspaceChange::Modified { entry })
+            }
+            RawChange::Removed(path) => self.removed(self.relative(&path)?),
+            RawChange::Renamed(from, to) => {
+                let from = self.relative(&from);
+                let entry = self.relative(&to).and_then(|to| self.entry(&to));
+                let from = from.filter(|from| {
+                    let is_dir = entry
+                        .as_ref()
+                        .is_some_and(|entry| entry.kind == EntryKind::Folder);
+                    !self.filter.is_ignored_entry(Path::new(from), is_dir)
+                });
+
+                match (from, entry) {
+                    (Some(from), Some(entry)) => Some(WorkspaceChange::Renamed {
+                        from,
+                        to: entry.path.clone(),
+                        entry,
+                    }),
+                    (None, Some(entry)) => Some(WorkspaceChange::Created { entry }),
+                    (Some(from), None) => Some(WorkspaceChange::Removed { path: from }),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    fn removed(&self, relative: String) -> Option<WorkspaceChange> {
+        (!self.filter.is_ignored_entry(Path::new(&relative), false))
+            .then_some(WorkspaceChange::Removed { path: relative })
+    }
+
+    fn entry(&self, relative: &str) -> Option<WorkspaceEntry> {
+        read_entry(&self.root, &self.root.join(relative), &self.filter)
+            .ok()
+            .flatten()
+    }
+
+    /// `None` for the root itself and for paths outside the workspace.
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok().or_else(|| {
+            self.canonical_root
+                .as_ref()
+                .and_then(|root| path.strip_prefix(root).ok())
+        })?;
+
+        let relative = to_slash_path(relative);
+        (!relative.is_empty()).then_some(relative)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RawChange {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed(PathBuf, PathBuf),
+}
+
+impl RawChange {
+    fn touches_ignore_file(&self) -> bool {
+        let is_ignore_file =
+            |path: &PathBuf| path.file_name().is_some_and(|name| name == IGNORE_FILE);
+
+        match self {
+            Self::Created(path) | Self::Modified(path) | Self::Removed(path) => {
+                is_ignore_file(path)
+            }
+            Self::Renamed(from, to) => is_ignore_file(from) || is_ignore_file(to),
+        }
+    }
+}
+
+/// Collapses raw notify events into one change per path and pairs the two
+/// halves of a rename.
+#[derive(Debug, Default)]
+struct RawBatch {
+    changes: Vec<Option<RawChange>>,
+    /// Position of the latest create/modify/remove for each path.
+    latest: HashMap<PathBuf, usize>,
+    /// Rename sources still waiting for their destination.
+    pending_renames: Vec<(Option<usize>, PathBuf)>,
+    paired_trackers: HashSet<usize>,
+    rescan: bool,
+}
+
+impl RawBatch {
+    fn add(&mut self, message: notify::Result<Event>) {
+        let Ok(event) = message else {
+            self.rescan = true;
+            return;
+        };
+
+        if event.need_rescan() {
+            self.rescan = true;
+        }
+
+        let tracker = event.tracker();
+        match event.kind {
+            EventKind::Create(_) => {
+                for path in event.paths {
+                    self.push(RawChange::Created(path));
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in event.paths {
+                    self.push(RawChange::Removed(path));
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(mode)) => {
+                self.add_rename(mode, tracker, event.paths)
+            }
+            EventKind::Modify(_) | EventKind::Any | EventKind::Other => {
+                for path in event.paths {
+                    self.push(RawChange::Modified(path));
+                }
+            }
+            EventKind::Access(_) => {}
+        }
+    }
+
+    fn add_rename(&mut self, mode: RenameMode, tracker: Option<usize>, paths: Vec<PathBuf>) {
+        match mode {
+            RenameMode::Both => {
+                // inotify also reports the halves, which were paired already.
+                if tracker.is_some_and(|tracker| self.paired_trackers.contains(&tracker)) {
+                    return;
+                }
+
+                if let [from, to] = paths.as_slice() {
+                    self.push(RawChange::Renamed(from.clone(), to.clone()));
+                }
+            }
+            RenameMode::From => {
+                for path in paths {
+                    self.pending_renames.push((tracker, path));
+                }
+            }
+            RenameMode::To => {
+                for path in paths {
+                    let source = self
+                        .pending_renames
+                        .iter()
+                        .rposition(|(pending, _)| *pending == tracker);
+
+                    match source {
+                        Some(index) => {
+                            let (_, from) = self.pending_renames.remove(index);
+                            if let Some(tracker) = tracker {
+                                self.paired_trackers.insert(tracker);
+                            }
+                            self.push(RawChange::Renamed(from, path));
+                        }
+                        None => self.push(RawChange::Created(path)),
+                    }
+                }
+            }
+            // Backends that cannot tell the halves apart (FSEvents) report
+            // each path on its own.
+            RenameMode::Any | RenameMode::Other => {
+                for path in paths {
+                    if path.exists() {
+                        self.push(RawChange::Created(path));
+                    } else {
+                        self.push(RawChange::Removed(path));
+                    }
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, change: RawChange) {
+        let path = match &change {
+            RawChange::Created(path) | RawChange::Modified(path) | RawChange::Removed(path) => {
+                path.clone()
+            }
+            RawChange::Renamed(from, to) => {
+                self.latest.remove(from);
+                self.latest.remove(to);
+                self.changes.push(Some(change));
+                return;
+            }
+        };
+
+        if let Some(&index) = self.latest.get(&path) {
+            let merged = match (&self.changes[index], &change) {
+                (Some(RawChange::Created(_)), RawChange::Created(_) | RawChange::Modified(_))
+                | (Some(RawChange::Modified(_)), RawChange::Modified(_))
+                | (Some(RawChange::Removed(_)), RawChange::Removed(_)) => return,
+                (Some(RawChange::Created(_)), RawChange::Removed(_)) => Some(None),
+                (Some(RawChange::Modified(_)), RawChange::Removed(_)) => Some(Some(change.clone())),
+                // Editors that save by deleting and recreating the file.
+                (Some(RawChange::Removed(_)), RawChange::Created(_) | RawChange::Modified(_)) => {
+                    Some(Some(RawChange::Modified(path.clone())))
+                }
+                _ => None,
+            };
+
+            if let Some(merged) = merged {
+                if merged.is_none() {
+                    self.latest.remove(&path);
+                }
+                self.changes[index] = merged;
+                return;
+            }
+        }
+
+        self.latest.insert(path, self.changes.len());
+        self.changes.push(Some(change));
+    }
+
+    /// Unpaired rename sources left the workspace and count as removals.
+    fn finish(mut self) -> (Vec<RawChange>, bool) {
+        for (_, path) in std::mem::take(&mut self.pending_renames) {
+            self.push(RawChange::Removed(path));
+        }
+
+        (self.changes.into_iter().flatten().collect(), self.rescan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, sync::mpsc, time::Duration};
+
+    use notify::{
+        Event, EventKind,
+        event::{CreateKind, DataChange, ModifyKind, RemoveKind, RenameMode},
+    };
+    use tempfile::tempdir;
+
+    use super::{RawBatch, RawChange, WorkspaceChange, WorkspaceChanged, WorkspaceWatcher};
+
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from("/lore").join(name)
+    }
+
+    fn event(kind: EventKind, name: &str) -> notify::Result<Event> {
+        Ok(Event::new(kind).add_path(path(name)))
+    }
+
+    fn rename(mode: RenameMode, tracker: usize, names: &[&str]) -> notify::Result<Event> {
+        let mut event = Event::new(EventKind::Modify(ModifyKind::Name(mode))).set_tracker(tracker);
+        for name in names {
+            event = event.add_path(path(name));
+        }
+        Ok(event)
+    }
+
+    const CREATE: EventKind = EventKind::Create(CreateKind::File);
+    const MODIFY: EventKind = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+    const REMOVE: EventKind = EventKind::Remove(RemoveKind::File);
+
+    #[test]
+    fn collapses_events_per_path() {
+        let mut batch = RawBatch::default();
+        batch.add(event(CREATE, "new.md"));
+        batch.add(event(MODIFY, "new.md"));
+        batch.add(event(CREATE, "scratch.md"));
+        batch.add(event(REMOVE, "scratch.md"));
+        batch.add(event(REMOVE, "saved.md"));
+        batch.add(event(CREATE, "saved.md"));
+        batch.add(event(MODIFY, "gone.md"));
+        batch.add(event(REMOVE, "gone.md"));
+
+        let (changes, rescan) = batch.finish();
+
+        assert_eq!(
+            changes,
+            [
+                RawChange::Created(path("new.md")),
+                RawChange::Modified(path("saved.md")),
+                RawChange::Removed(path("gone.md")),
+            ]
+        );
+        assert!(!rescan);
+    }
+
+    #[test]
+    fn pairs_rename_halves_once() {
+        let mut batch = RawBatch::default();
+        batch.add(rename(RenameMode::From, 7, &["old.md"]));
+        batch.add(rename(RenameMode::To, 7, &["new.md"]));
+        batch.add(rename(RenameMode::Both, 7, &["old.md", "new.md"]));
+
+        let (changes, _) = batch.finish();
+
+        assert_eq!(
+            changes,
+            [RawChange::Renamed(path("old.md"), path("new.md"))]
+        );
+    }
+
+    #[test]
+    fn treats_unpaired_rename_halves_as_create_and_remove() {
+        let mut batch = RawBatch::default();
+        batch.add(rename(RenameMode::From, 1, &["left.md"]));
+        batch.add(rename(RenameMode::To, 2, &["arrived.md"]));
+        batch.add(Err(notify::Error::generic("queue overflow")));
+
+        let (changes, rescan) = batch.finish();
+
+        assert_eq!(
+            changes,
+            [
+                RawChange::Created(path("arrived.md")),
+                RawChange::Removed(path("left.md")),
+            ]
+        );
+        assert!(rescan);
+    }
+
+    fn next_batch(receiver: &mpsc::Receiver<WorkspaceChanged>) -> WorkspaceChanged {
+        receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("watcher reported changes")
+    }
+
+    #[test]
+    fn reports_external_edits_and_skips_ignored_paths() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join(".lore")).expect("create internal dir");
+        fs::write(root.join(".loreignore"), "*.tmp\n").expect("write ignore");
+
+        let (sender, receiver) = mpsc::channel();
+        let _watcher =
+            WorkspaceWatcher::with_debounce(root, Duration::from_millis(50), move |changed| {
+                sender.send(changed).ok();
+            })
+            .expect("watcher started");
+
+        fs::write(root.join(".lore/state.json"), "{}").expect("write internal file");
+        fs::write(root.join("draft.tmp"), "").expect("write ignored file");
+        fs::write(root.join("Maren.character.md"), "# Maren").expect("write document");
+
+        let changed = next_batch(&receiver);
+
+        assert_eq!(changed.changes.len(), 1);
+        let WorkspaceChange::Created { entry } = &changed.changes[0] else {
+            panic!("expected a created entry, got {:?}", changed.changes);
+        };
+        assert_eq!(entry.path, "Maren.character.md");
+        assert_eq!(entry.size, 7);
+    }
+
+    #[test]
+    fn reports_renames_with_the_new_entry() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("Places")).expect("create folder");
+        fs::write(root.join("Places/Saltreach.md"), "").expect("write document");
+
+        let (sender, receiver) = mpsc::channel();
+        let _watcher =
+            WorkspaceWatcher::with_debounce(root, Duration::from_millis(50), move |changed| {
+                sender.send(changed).ok();
+            })
+            .expect("watcher started");
+
+        fs::rename(
+            root.join("Places/Saltreach.md"),
+            root.join("Places/Saltreach.location.md"),
+        )
+        .expect("rename document");
+
+        let changed = next_batch(&receiver);
+
+        assert_eq!(changed.changes.len(), 1);
+        let WorkspaceChange::Renamed { from, to, entry } = &changed.changes[0] else {
+            panic!("expected a rename, got {:?}", changed.changes);
+        };
+        assert_eq!(from, "Places/Saltreach.md");
+        assert_eq!(to, "Places/Saltreach.location.md");
+        assert_eq!(entry.entity_type.as_deref(), Some("location"));
+    }
+}